@@ -0,0 +1,102 @@
+use itertools::Itertools;
+use crate::{code::Code, constraint::Constraint, error::Error, problem::Problem, solve::{Assignment, CommandLineVerifier, Solver}};
+
+// A valid assignment of laws to the verifiers, along with the code it defines
+#[derive(Clone)]
+pub struct World {
+    pub assignment: Vec<Assignment>,
+    pub code: Code,
+}
+
+// The possible laws of a verifier, with the number of worlds where it uses each of them
+pub struct VerifierSummary {
+    pub verifier: usize,
+    pub laws: Vec<(Assignment, usize)>,
+}
+
+impl VerifierSummary {
+    // The law of the verifier is known by deduction alone
+    pub fn is_determined(&self) -> bool {
+        self.laws.len() == 1
+    }
+}
+
+pub struct Analysis {
+    pub worlds: Vec<World>,
+    num_verifiers: usize,
+}
+
+fn same_assignment(a: &Assignment, b: &Assignment) -> bool {
+    a.card == b.card && a.law.constraint == b.law.constraint
+}
+
+// Enumerates the worlds of a problem, with the same rules as the solver
+pub fn analyze(problem: &Problem) -> Result<Analysis, Error> {
+    // The deductions of the solver only remove constraints that belong to no world, so they speed up the enumeration
+    let solver = Solver::<CommandLineVerifier>::from_problem(problem)?;
+    let worlds = solver.worlds().map(|constraints| {
+        let code = Constraint::inter(constraints.iter().copied()).solution().expect("Worlds have a unique solution");
+        let assignment = constraints.iter().enumerate()
+            .map(|(v, &c)| solver.assignment_of(v, c).expect("Every constraint comes from a card of the problem"))
+            .collect();
+        World { assignment, code }
+    }).collect_vec();
+    Ok(Analysis { worlds, num_verifiers: problem.num_verifiers() })
+}
+
+impl Analysis {
+    // The possible codes, with the number of worlds leading to each of them
+    pub fn codes(&self) -> Vec<(Code, usize)> {
+        self.worlds.iter().map(|w| w.code).counts().into_iter()
+            .sorted_by_key(|(c, n)| (std::cmp::Reverse(*n), *c))
+            .collect()
+    }
+
+    // The code when every world leads to it, in which case the game is solved without asking any question
+    pub fn solution(&self) -> Option<Code> {
+        let code = self.worlds[0].code;
+        self.worlds.iter().all(|w| w.code == code).then_some(code)
+    }
+
+    pub fn is_determined(&self) -> bool {
+        self.solution().is_some()
+    }
+
+    pub fn verifiers(&self) -> Vec<VerifierSummary> {
+        (0..self.num_verifiers).map(|verifier| {
+            let mut laws: Vec<(Assignment, usize)> = Vec::new();
+            for a in self.worlds.iter().map(|w| &w.assignment[verifier]) {
+                match laws.iter_mut().find(|(b, _)| same_assignment(a, b)) {
+                    Some((_, n)) => *n += 1,
+                    None => laws.push((a.clone(), 1)),
+                }
+            }
+            laws.sort_by_key(|(a, n)| (std::cmp::Reverse(*n), a.card, a.law.id));
+            VerifierSummary { verifier, laws }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::problem::Problem;
+    use super::analyze;
+
+    #[test]
+    pub fn worlds_of_a_problem() {
+        let problem: Problem = "normal 4 9 11 14".parse().unwrap();
+        let analysis = analyze(&problem).unwrap();
+        let codes = analysis.codes();
+        assert_eq!(codes.iter().map(|(_, n)| n).sum::<usize>(), analysis.worlds.len());
+        assert!(analysis.worlds.iter().all(|w| w.assignment.iter().all(|a| a.law.accepts(w.code))));
+
+        assert!(!analysis.is_determined());
+        assert!(analyze(&"normal 4 7 13 15".parse().unwrap()).unwrap().is_determined());
+
+        let verifiers = analysis.verifiers();
+        assert_eq!(verifiers.len(), 4);
+        for v in verifiers.iter() {
+            assert_eq!(v.laws.iter().map(|(_, n)| n).sum::<usize>(), analysis.worlds.len());
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path, time::{Duration, Instant}};
+use itertools::Itertools;
+use json::JsonValue;
+use crate::{catalogue::Catalogue, error::Error, games::{Difficulty, GameRecord}, problem::ProblemMode, solve::{AutomaticVerifier, Solver, Strategy}};
+
+// The result of the solver compared with the official AI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Outcome {
+    Win, Tie, Loss, Failure
+}
+
+// The result of the solver on a game of the database
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub hash: String,
+    pub mode: ProblemMode,
+    pub difficulty: Difficulty,
+    pub num_verifiers: usize,
+    pub solved: Option<(usize, usize)>, // The number of rounds and questions, unless the solver failed
+    pub questions_ai: usize,
+    pub time: Duration,
+}
+
+// Totals over a group of games
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub games: usize,
+    pub outcomes: BTreeMap<Outcome, usize>,
+    pub rounds: usize,
+    pub questions: usize,
+    pub questions_ai: usize,
+    pub time: Duration,
+}
+
+// A game that the solver handles worse than in the baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub hash: String,
+    pub baseline: Option<usize>,
+    pub questions: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub results: Vec<GameResult>,
+}
+
+impl Outcome {
+    fn all() -> [Outcome; 4] {
+        [Outcome::Win, Outcome::Tie, Outcome::Loss, Outcome::Failure]
+    }
+}
+
+impl GameResult {
+    // Solves a game with the official laws, and times the solver. Generated games have no AI to compare with, so they give None.
+    pub fn run(record: &GameRecord, catalogue: &Catalogue) -> Option<GameResult> {
+        Self::run_with(record, catalogue, Strategy::default())
+    }
+
+    pub fn run_with(record: &GameRecord, catalogue: &Catalogue, strategy: Strategy) -> Option<GameResult> {
+        let (difficulty, mut questions_ai) = (record.difficulty?, record.num_questions_ai?);
+        let start = Instant::now();
+        let solved = record.problem_in(catalogue).ok().and_then(|p| {
+            let mut solver = Solver::<AutomaticVerifier>::new(&p).strategy(strategy).automatic_laws(catalogue, &record.laws).ok()?;
+            let sol = solver.solve().ok()?;
+            (sol.code == record.solution).then(|| (solver.num_rounds(), solver.num_questions()))
+        });
+
+        // The official AI seems to count the questions differently in extreme and nightmare modes
+        if record.mode != ProblemMode::Normal {
+            questions_ai = (questions_ai as f32 * 1.5).ceil() as usize;
+        }
+
+        Some(GameResult {
+            hash: record.hash.clone(),
+            mode: record.mode,
+            difficulty,
+            num_verifiers: record.laws.len(),
+            solved,
+            questions_ai,
+            time: start.elapsed(),
+        })
+    }
+
+    pub fn questions(&self) -> Option<usize> {
+        self.solved.map(|(_, q)| q)
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        match self.questions() {
+            None => Outcome::Failure,
+            Some(q) if q < self.questions_ai => Outcome::Win,
+            Some(q) if q == self.questions_ai => Outcome::Tie,
+            Some(_) => Outcome::Loss,
+        }
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let (rounds, questions) = self.solved.map_or((None, None), |(r, q)| (Some(r), Some(q)));
+        json::object! {
+            "hash": self.hash.clone(),
+            "mode": self.mode.to_string(),
+            "difficulty": self.difficulty.to_string(),
+            "verifiers": self.num_verifiers,
+            "rounds": rounds,
+            "questions": questions,
+            "questions-ai": self.questions_ai,
+            "outcome": self.outcome().to_string(),
+            "time-ms": self.time.as_secs_f64() * 1000.0,
+        }
+    }
+
+    fn from_json(obj: &JsonValue) -> Result<GameResult, String> {
+        let str_field = |key: &str| obj[key].as_str().ok_or_else(|| format!("Missing field {}", key));
+        let usize_field = |key: &str| obj[key].as_usize().ok_or_else(|| format!("Missing field {}", key));
+        let solved = match (obj["rounds"].as_usize(), obj["questions"].as_usize()) {
+            (Some(r), Some(q)) => Some((r, q)),
+            _ => None,
+        };
+        Ok(GameResult {
+            hash: str_field("hash")?.into(),
+            mode: str_field("mode")?.parse().map_err(|e: Error| e.to_string())?,
+            difficulty: str_field("difficulty")?.parse().map_err(|e: Error| e.to_string())?,
+            num_verifiers: usize_field("verifiers")?,
+            solved,
+            questions_ai: usize_field("questions-ai")?,
+            time: Duration::from_secs_f64(obj["time-ms"].as_f64().unwrap_or(0.0) / 1000.0),
+        })
+    }
+}
+
+impl Summary {
+    pub fn add(&mut self, result: &GameResult) {
+        self.games += 1;
+        *self.outcomes.entry(result.outcome()).or_default() += 1;
+        if let Some((r, q)) = result.solved {
+            self.rounds += r;
+            self.questions += q;
+            self.questions_ai += result.questions_ai;
+        }
+        self.time += result.time;
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.outcomes.get(&outcome).copied().unwrap_or(0)
+    }
+
+    // The means only cover the games that were solved
+    pub fn mean_rounds(&self) -> f32 {
+        self.rounds as f32 / (self.games - self.count(Outcome::Failure)).max(1) as f32
+    }
+
+    pub fn mean_questions(&self) -> f32 {
+        self.questions as f32 / (self.games - self.count(Outcome::Failure)).max(1) as f32
+    }
+
+    pub fn mean_questions_ai(&self) -> f32 {
+        self.questions_ai as f32 / (self.games - self.count(Outcome::Failure)).max(1) as f32
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let mut obj = json::object! {
+            "games": self.games,
+            "rounds": self.rounds,
+            "questions": self.questions,
+            "questions-ai": self.questions_ai,
+            "mean-rounds": self.mean_rounds(),
+            "mean-questions": self.mean_questions(),
+            "mean-questions-ai": self.mean_questions_ai(),
+            "time-ms": self.time.as_secs_f64() * 1000.0,
+        };
+        for outcome in Outcome::all() {
+            obj[outcome.to_string()] = self.count(outcome).into();
+        }
+        obj
+    }
+}
+
+impl Report {
+    // Solves the official games among the records, leaving out the generated ones
+    pub fn run<'a>(records: impl Iterator<Item = &'a GameRecord>, catalogue: &Catalogue) -> Report {
+        Report { results: records.filter_map(|r| GameResult::run(r, catalogue)).collect() }
+    }
+
+    pub fn total(&self) -> Summary {
+        self.results.iter().fold(Summary::default(), |mut s, r| {
+            s.add(r);
+            s
+        })
+    }
+
+    // The totals for every mode and difficulty
+    pub fn by_mode_and_difficulty(&self) -> BTreeMap<(ProblemMode, Difficulty), Summary> {
+        let mut summaries: BTreeMap<_, Summary> = BTreeMap::new();
+        for r in self.results.iter() {
+            summaries.entry((r.mode, r.difficulty)).or_default().add(r);
+        }
+        summaries
+    }
+
+    pub fn to_csv(&self) -> String {
+        let header = "hash,mode,difficulty,verifiers,rounds,questions,questions_ai,outcome,time_ms";
+        let rows = self.results.iter().map(|r| {
+            let (rounds, questions) = r.solved.map_or((String::new(), String::new()), |(r, q)| (r.to_string(), q.to_string()));
+            format!("{},{},{},{},{},{},{},{},{:.3}", r.hash, r.mode, r.difficulty, r.num_verifiers, rounds, questions,
+                r.questions_ai, r.outcome(), r.time.as_secs_f64() * 1000.0)
+        });
+        std::iter::once(header.to_string()).chain(rows).map(|l| l + "\n").collect()
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let summaries = self.by_mode_and_difficulty().into_iter().map(|((mode, difficulty), s)| {
+            let mut obj = s.to_json();
+            obj["mode"] = mode.to_string().into();
+            obj["difficulty"] = difficulty.to_string().into();
+            obj
+        }).collect_vec();
+        json::object! {
+            "games": self.results.iter().map(|r| r.to_json()).collect_vec(),
+            "summary": summaries,
+            "total": self.total().to_json(),
+        }
+    }
+
+    pub fn from_json(s: &str) -> Result<Report, String> {
+        let root = json::parse(s).map_err(|e| e.to_string())?;
+        let results = root["games"].members().map(GameResult::from_json).collect::<Result<_, _>>()?;
+        Ok(Report { results })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Report, String> {
+        Self::from_json(&fs::read_to_string(path).map_err(|e| e.to_string())?)
+    }
+
+    // The games that need more questions than in the baseline, or that are not solved anymore
+    pub fn regressions(&self, baseline: &Report) -> Vec<Regression> {
+        self.results.iter().filter_map(|r| {
+            let b = baseline.results.iter().find(|b| b.hash == r.hash)?;
+            let worse = match (b.questions(), r.questions()) {
+                (_, None) => b.questions().is_some(),
+                (None, Some(_)) => false,
+                (Some(bq), Some(q)) => q > bq,
+            };
+            worse.then(|| Regression { hash: r.hash.clone(), baseline: b.questions(), questions: r.questions() })
+        }).collect()
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Tie => write!(f, "tie"),
+            Outcome::Loss => write!(f, "loss"),
+            Outcome::Failure => write!(f, "failure"),
+        }
+    }
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let questions = |q: Option<usize>| q.map_or("a failure".to_string(), |q| format!("{} questions", q));
+        write!(f, "Game {} needs {} instead of {}", self.hash, questions(self.questions), questions(self.baseline))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{catalogue::Catalogue, games::{GameDatabase, GAMES_PATH}};
+    use super::{Outcome, Report};
+
+    #[test]
+    pub fn reports_and_regressions() {
+        let db = GameDatabase::load(GAMES_PATH).unwrap();
+        let report = Report::run(db.records().iter().take(3), Catalogue::builtin());
+        let total = report.total();
+        assert_eq!(total.games, 3);
+        assert_eq!(total.count(Outcome::Failure), 0);
+        assert_eq!(report.to_csv().lines().count(), 4);
+
+        let parsed = Report::from_json(&report.to_json().dump()).unwrap();
+        assert_eq!(parsed.results.len(), 3);
+        assert!(report.regressions(&parsed).is_empty());
+
+        let mut worse = report.clone();
+        worse.results[2].solved = None;
+        assert_eq!(worse.regressions(&report).len(), 1);
+    }
+}
@@ -0,0 +1,56 @@
+use itertools::Itertools;
+use turing_machine_game::analyze::analyze;
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main analyze [OPTIONS] PROBLEM
+
+Lists every valid assignment of laws to the verifiers with the code it leads to,
+and shows which verifiers are already determined by deduction alone.
+
+Options:
+  --catalogue FILE     Reads the cards from a catalogue instead of the official one
+  --input FORMAT       How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  -h, --help           Prints this help";
+
+fn assignments(n: usize) -> String {
+    if n == 1 { "1 assignment".into() } else { format!("{} assignments", n) }
+}
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let (problem, _) = args::problem(&catalogue, format, &args.positional()?)?;
+    let analysis = analyze(&problem).map_err(|e| format!("{}.", e))?;
+    let letters = "ABCDEF".chars().collect_vec();
+    print!("{}", problem);
+
+    println!();
+    println!("{} valid assignments of laws:", analysis.worlds.len());
+    for world in analysis.worlds.iter() {
+        let laws = world.assignment.iter().map(|a| format!("{}: {}", letters[a.verifier], a.describe(problem.mode))).join(", ");
+        println!("{} │ {}", world.code, laws);
+    }
+
+    println!();
+    if let Some(code) = analysis.solution() {
+        println!("The code {} is determined by deduction alone.", code);
+    } else {
+        println!("Possible codes:");
+        for (code, n) in analysis.codes() {
+            println!("{}: {}", code, assignments(n));
+        }
+    }
+
+    println!();
+    println!("Possible laws of the verifiers:");
+    for summary in analysis.verifiers() {
+        let letter = letters[summary.verifier];
+        if summary.is_determined() {
+            println!("{}: {} (determined by deduction)", letter, summary.laws[0].0.describe(problem.mode));
+        } else {
+            let laws = summary.laws.iter().map(|(a, n)| format!("{} in {}", a.describe(problem.mode), assignments(*n))).join(", ");
+            println!("{}: {}", letter, laws);
+        }
+    }
+    Ok(())
+}
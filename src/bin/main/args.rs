@@ -0,0 +1,128 @@
+use std::{fmt::Display, str::FromStr};
+use turing_machine_game::{catalogue::Catalogue, error::Error, games::{GameDatabase, GAMES_PATH}, problem::Problem, solve::Answer};
+
+// The arguments of a subcommand. Options can be given in any order, and what remains are the positional arguments.
+pub struct Args(Vec<String>);
+
+// How the problem is written on the command line
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputFormat {
+    #[default]
+    Auto, // A hash of the database if there is one, otherwise a mode followed by card ids when there are several words, a share code otherwise
+    Text,
+    Share,
+    Game, // The hash of a game of the database
+    Json,
+}
+
+impl Args {
+    pub fn new(args: impl Iterator<Item = String>) -> Args {
+        Args(args.collect())
+    }
+
+    // Removes the first occurrence of a flag, and returns whether it was given.
+    // The options are read before the flags, so that a flag given as the value of an option is rejected by option() instead.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let Some(i) = self.0.iter().position(|a| a == name) else { return false };
+        self.0.remove(i);
+        true
+    }
+
+    pub fn help(&mut self) -> bool {
+        self.flag("--help") | self.flag("-h")
+    }
+
+    // Removes an option along with its value, and parses the value
+    pub fn option<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> where T::Err: Display {
+        let Some(i) = self.0.iter().position(|a| a == name) else { return Ok(None) };
+        // A value never starts with --, so that a flag right after an option is not taken as its value
+        if self.0.get(i + 1).is_none_or(|v| v.starts_with("--")) {
+            return Err(format!("Option {} needs a value.", name));
+        }
+        let value = self.0.drain(i..i + 2).nth(1).unwrap();
+        value.parse().map(Some).map_err(|e| format!("Invalid value for {}: {}.", name, e))
+    }
+
+    // Removes every occurrence of an option that can be repeated
+    pub fn options<T: FromStr>(&mut self, name: &str) -> Result<Vec<T>, String> where T::Err: Display {
+        let mut values = Vec::new();
+        while let Some(value) = self.option(name)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    // The positional arguments, once all the options were removed
+    pub fn positional(self) -> Result<Vec<String>, String> {
+        match self.0.iter().find(|a| a.starts_with("--")) {
+            Some(option) => Err(format!("Unknown option {}, see --help for the arguments of the command.", option)),
+            None => Ok(self.0),
+        }
+    }
+
+    // Checks that nothing is left, for the commands without positional arguments
+    pub fn finish(self) -> Result<(), String> {
+        match self.positional()?.first() {
+            Some(word) => Err(format!("Unexpected argument {}, see --help for the arguments of the command.", word)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(InputFormat::Auto),
+            "text" => Ok(InputFormat::Text),
+            "share" => Ok(InputFormat::Share),
+            "game" => Ok(InputFormat::Game),
+            "json" => Ok(InputFormat::Json),
+            _ => Err(format!("'{}' is not an input format, it should be auto, text, share, game or json", s)),
+        }
+    }
+}
+
+// Reads the catalogue given with --catalogue, or the official one
+pub fn catalogue(args: &mut Args) -> Result<Catalogue, String> {
+    match args.option::<String>("--catalogue")? {
+        Some(path) => Catalogue::load(&path).map_err(|e| format!("Could not load catalogue {}: {}", path, e)),
+        None => Ok(Catalogue::builtin().clone()),
+    }
+}
+
+// Splits the words of a problem from the answers that follow it, written like 241:A=y
+pub fn split_answers(words: &[String]) -> Result<(&[String], Vec<Answer>), String> {
+    let (problem, answers) = words.split_at(words.iter().position(|w| w.parse::<Answer>().is_ok()).unwrap_or(words.len()));
+    let answers = answers.iter().map(|a| a.parse()).collect::<Result<_, Error>>().map_err(|e| format!("{}.", e))?;
+    Ok((problem, answers))
+}
+
+// Reads a problem in the given format, along with the laws of its verifiers when they are known
+pub fn problem(catalogue: &Catalogue, format: InputFormat, words: &[String]) -> Result<(Problem, Option<Vec<u8>>), String> {
+    if words.is_empty() {
+        return Err("Please pass the mode of the problem and the IDs of the cards, or a share code.".into());
+    }
+    let text = words.join(" ");
+    let in_database = || GameDatabase::load(GAMES_PATH).is_ok_and(|db| db.by_hash(&text).is_some());
+    let format = match format {
+        InputFormat::Auto if in_database() => InputFormat::Game,
+        f => f,
+    };
+    let parsed = match format {
+        InputFormat::Auto => Problem::from_text_or_share_code(catalogue, &text),
+        InputFormat::Share => Problem::from_share_code(catalogue, &text),
+        InputFormat::Game => {
+            let db = GameDatabase::load(GAMES_PATH).map_err(|e| e.to_string())?;
+            let record = db.by_hash(&text).ok_or_else(|| format!("Game {} is not in the database {}.", text, GAMES_PATH))?;
+            record.problem_in(catalogue).map(|p| (p, Some(record.laws.clone())))
+        }
+        InputFormat::Json => {
+            let obj = json::parse(&text).map_err(|e| format!("Invalid JSON: {}.", e))?;
+            Problem::from_json(catalogue, &obj).map(|p| (p, None))
+        }
+        InputFormat::Text => Problem::from_text(catalogue, &text).map(|p| (p, None)),
+    };
+    parsed.map_err(|e| format!("{}.", e))
+}
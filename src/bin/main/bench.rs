@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, fs};
+use colored::Colorize;
+use itertools::Itertools;
+use turing_machine_game::{analyze::analyze, bench::{GameResult, Outcome, Report}, catalogue::Catalogue, difficulty::{DifficultyModel, Features, BUILTIN_MODEL}, evaluate::Evaluation, games::{GameDatabase, GAMES_PATH}, solve::Strategy};
+use crate::args::Args;
+
+fn print_result(r: &GameResult) {
+    let (mode, diff, cards) = (r.mode.to_string(), r.difficulty.to_string(), r.num_verifiers.to_string());
+    let time = format!("{:.0}ms", r.time.as_secs_f64() * 1000.0);
+    match r.solved {
+        Some((rounds, questions)) => {
+            let color = match r.outcome() {
+                Outcome::Win => "green",
+                Outcome::Tie => "yellow",
+                _ => "red",
+            };
+            let questions = questions.to_string().color(color);
+            let questions_ai = r.questions_ai.to_string().color(color);
+            println!("{:^11}│{:^12}│{:^7}│{:^4}│{:^4}│{:^4}│{:>8}", mode, diff, cards, rounds, questions, questions_ai, time);
+        }
+        None => println!("{}", format!("{:^11}│{:^12}│{:^7}│    │    │    │{:>8}", mode, diff, cards, time).on_red()),
+    }
+}
+
+fn print_summary(report: &Report) {
+    println!("   mode    │ difficulty │ games │ W  │ T  │ L  │ F  │ Q avg │ QAI avg │   time");
+    println!("───────────┼────────────┼───────┼────┼────┼────┼────┼───────┼─────────┼─────────");
+    let total = (("all".to_string(), "all".to_string()), report.total());
+    let summaries = report.by_mode_and_difficulty().into_iter().map(|((m, d), s)| ((m.to_string(), d.to_string()), s));
+    for ((mode, diff), s) in summaries.chain(std::iter::once(total)) {
+        println!("{:^11}│{:^12}│{:^7}│{:^4}│{:^4}│{:^4}│{:^4}│{:^7.2}│{:^9.2}│{:>8.0}ms", mode, diff, s.games,
+            s.count(Outcome::Win), s.count(Outcome::Tie), s.count(Outcome::Loss), s.count(Outcome::Failure),
+            s.mean_questions(), s.mean_questions_ai(), s.time.as_secs_f64() * 1000.0);
+    }
+}
+
+// Compares the estimated difficulty of the games with the one of the official website
+fn difficulty_report(db: &GameDatabase) {
+    let mut samples = Vec::new();
+    for record in db.records() {
+        // Generated games have no official difficulty
+        let Some(difficulty) = record.difficulty else { continue };
+        match record.problem().and_then(|p| Features::of(&p)) {
+            Ok(f) => samples.push((f, difficulty)),
+            Err(e) => println!("{}", format!("Game {} is invalid: {}", record.hash, e).on_red()),
+        }
+    }
+
+    println!("   mode    │ difficulty │ cards │ estimate");
+    println!("───────────┼────────────┼───────┼──────────");
+    for (f, diff) in samples.iter() {
+        let estimate = BUILTIN_MODEL.estimate(f);
+        let estimate = estimate.to_string().color(if estimate == *diff { "green" } else { "red" });
+        println!("{:^11}│{:^12}│{:^7}│{:^10}", f.mode.to_string(), diff.to_string(), f.num_verifiers.to_string(), estimate);
+    }
+
+    // Leaving each game out of the calibration shows how well the model generalizes to new games
+    let left_out = (0..samples.len()).filter(|&i| {
+        let others: Vec<_> = samples.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, s)| *s).collect();
+        DifficultyModel::calibrate(&others).estimate(&samples[i].0) == samples[i].1
+    }).count();
+
+    println!();
+    println!("Accuracy of the built-in model: {:.0}%", 100.0 * BUILTIN_MODEL.accuracy(&samples));
+    println!("Accuracy on games left out of the calibration: {:.0}%", 100.0 * left_out as f32 / samples.len() as f32);
+    println!("Model calibrated on all the games: {:?}", DifficultyModel::calibrate(&samples));
+}
+
+// Formats a distribution as "value:count" pairs
+fn format_distribution(d: &BTreeMap<usize, usize>) -> String {
+    d.iter().map(|(v, n)| format!("{}:{}", v, n)).join(" ")
+}
+
+// Runs the solver against every possible assignment of laws of every game, instead of the official one
+fn exhaustive_report(db: &GameDatabase, max_worlds: usize) {
+    println!("   mode    │ difficulty │ cards │ worlds │ R avg │ R max │ Q avg │ Q max");
+    println!("───────────┼────────────┼───────┼────────┼───────┼───────┼───────┼───────");
+
+    let mut totals: BTreeMap<String, Evaluation> = BTreeMap::new();
+    for record in db.records() {
+        let evaluation = match record.problem().and_then(|p| Evaluation::sample(&p, max_worlds)) {
+            Ok(e) => e,
+            Err(e) => {
+                println!("{}", format!("Game {} is invalid: {}", record.hash, e).on_red());
+                continue;
+            }
+        };
+        let row = format!("{:^11}│{:^12}│{:^7}│{:^8}│{:^7.2}│{:^7}│{:^7.2}│{:^7}",
+            record.mode.to_string(), record.difficulty.map_or("-".into(), |d| d.to_string()), record.laws.len(), evaluation.num_worlds,
+            evaluation.mean_rounds(), evaluation.worst_rounds(), evaluation.mean_questions(), evaluation.worst_questions());
+        if evaluation.failures > 0 { println!("{}", row.on_red()) } else { println!("{}", row) }
+        totals.entry(record.mode.to_string()).or_default().merge(&evaluation);
+    }
+
+    for (mode, evaluation) in totals.iter() {
+        println!();
+        println!("{} mode, {} worlds solved, {} failures", mode, evaluation.questions.len(), evaluation.failures);
+        println!("Rounds: average {:.2}, worst {}, distribution {}", evaluation.mean_rounds(), evaluation.worst_rounds(), format_distribution(&evaluation.rounds_distribution()));
+        println!("Questions: average {:.2}, worst {}, distribution {}", evaluation.mean_questions(), evaluation.worst_questions(), format_distribution(&evaluation.questions_distribution()));
+    }
+}
+
+pub const HELP: &str = "Usage: main bench [OPTIONS]
+
+Solves the games of data/games.json with their official laws, and compares the solver with the official AI.
+
+Options:
+  --strategy STRATEGY  rounds or questions, see the solve command [default: rounds]
+  --skip-trivial       Leaves out the games that are solved by deduction alone
+  --csv FILE           Saves the results as CSV
+  --json FILE          Saves the results and their summary as JSON
+  --baseline FILE      Fails if a game needs more questions than in a JSON report
+  --exhaustive         Solves every possible assignment of laws of the games instead of the official one
+  --max-worlds N       Limits the number of assignments solved per game with --exhaustive
+  --difficulty         Compares the estimated difficulty of the games with the official one
+  -h, --help           Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let strategy = args.option::<Strategy>("--strategy")?.unwrap_or_default();
+    let csv = args.option::<String>("--csv")?;
+    let json = args.option::<String>("--json")?;
+    let baseline = args.option::<String>("--baseline")?;
+    let max_worlds = args.option::<usize>("--max-worlds")?;
+    let skip_trivial = args.flag("--skip-trivial");
+    let exhaustive = args.flag("--exhaustive");
+    let difficulty = args.flag("--difficulty");
+    args.finish()?;
+    let db = GameDatabase::load(GAMES_PATH).map_err(|e| e.to_string())?;
+
+    if difficulty {
+        difficulty_report(&db);
+        return Ok(());
+    }
+    if exhaustive {
+        // The number of worlds solved per game can be limited, since nightmare games have thousands of them
+        exhaustive_report(&db, max_worlds.unwrap_or(usize::MAX));
+        return Ok(());
+    }
+
+    // Generated games have no official AI to compare with, and games solved by deduction alone say little about the choice of questions
+    let official = db.records().iter().filter(|r| r.is_official()).collect_vec();
+    let records = official.iter().copied()
+        .filter(|r| !skip_trivial || !r.problem().is_ok_and(|p| analyze(&p).is_ok_and(|a| a.is_determined())))
+        .collect_vec();
+    if official.len() < db.records().len() {
+        println!("{} generated games are skipped", db.records().len() - official.len());
+    }
+    if records.len() < official.len() {
+        println!("{} games solved by deduction alone are skipped", official.len() - records.len());
+    }
+    if records.len() < db.records().len() {
+        println!();
+    }
+
+    println!("   mode    │ difficulty │ cards │ R  │ Q  │ QAI│  time");
+    println!("───────────┼────────────┼───────┼────┼────┼────┼────────");
+    let mut report = Report::default();
+    for result in records.into_iter().filter_map(|r| GameResult::run_with(r, Catalogue::builtin(), strategy)) {
+        print_result(&result);
+        report.results.push(result);
+    }
+    println!();
+    print_summary(&report);
+
+    // The reports can be saved for other tools, or compared with a baseline to catch regressions
+    if let Some(path) = csv {
+        fs::write(&path, report.to_csv()).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    if let Some(path) = json {
+        fs::write(&path, report.to_json().pretty(2)).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    if let Some(path) = baseline {
+        let baseline = Report::load(&path).map_err(|e| format!("Could not read the baseline {}: {}", path, e))?;
+        let regressions = report.regressions(&baseline);
+        println!();
+        if !regressions.is_empty() {
+            for r in regressions.iter() {
+                println!("{}", r.to_string().red());
+            }
+            return Err(format!("{} regressions compared with {}", regressions.len(), path));
+        }
+        println!("{}", format!("No regression compared with {}", path).green());
+    }
+    Ok(())
+}
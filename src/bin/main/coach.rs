@@ -0,0 +1,47 @@
+use colored::Colorize;
+use turing_machine_game::coach::review;
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main coach [OPTIONS] PROBLEM ANSWER...
+
+Reviews a game played by hand, from the answers of the verifiers in the order they were asked.
+Answers are written like 241:A=y for a yes of verifier A to the code 241, or 241:A=n for a no.
+
+Options:
+  --catalogue FILE  Reads the cards from a catalogue instead of the official one
+  --input FORMAT    How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  -h, --help        Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let words = args.positional()?;
+    let (problem, answers) = args::split_answers(&words)?;
+    if answers.is_empty() {
+        return Err("Please pass the answers of the verifiers after the problem, in the order of the game, like 241:A=y 241:B=n.".into());
+    }
+    let (problem, _) = args::problem(&catalogue, format, problem)?;
+    let review = review(&problem, &answers).map_err(|e| format!("{}.", e))?;
+
+    println!("   answer   │ expected │ eliminated │ deduced │ solver would ask");
+    println!("────────────┼──────────┼────────────┼─────────┼──────────────────");
+    for q in review.questions.iter() {
+        let best = q.best.map_or("-".into(), |(code, verifier, expected)| {
+            format!("{} to {} ({:.1})", code, "ABCDEF".chars().nth(verifier).unwrap(), expected)
+        });
+        println!("{:^12}│{:^10.1}│{:^12}│{:^9}│ {}", q.answer.to_string(), q.expected, q.eliminated, q.deduced, best);
+        if let Some(code) = q.known_code {
+            println!("{}", format!("The code {} could already be deduced before this question.", code).yellow());
+        } else if q.is_wasted() {
+            println!("{}", "This question was wasted, its answer eliminated nothing.".red());
+        }
+    }
+
+    println!();
+    match review.solution {
+        Some(code) => println!("The answers are enough to deduce the code {}.", code),
+        None => println!("The answers are not enough to deduce the code."),
+    }
+    println!("Wasted questions: {}, questions asked once the code was known: {}.", review.num_wasted(), review.num_unneeded());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+use json::stringify;
+use turing_machine_game::{games::GameDatabase, generate::Generator, problem::ProblemMode};
+use crate::args::{self, Args};
+
+pub const HELP: &str = "Usage: main generate [OPTIONS]
+
+Generates new games with a unique solution, printed in the format of data/games.json.
+Their hash is a share code that contains their laws. They have no difficulty nor answer of the official AI,
+so the bench command leaves them out of the comparison with the AI.
+
+Options:
+  --catalogue FILE     Picks the cards from a catalogue instead of the official one
+  --seed N             Seed of the generator, printed on stderr when it is random
+  --mode MODE          normal, extreme or nightmare [default: random]
+  --verifiers N        Number of verifiers, between 4 and 6 [default: random]
+  --count N            Number of games to generate [default: 1]
+  --append DATABASE    Adds the games to a database, which is created if needed
+  --skip-trivial       Leaves out the games that are solved by deduction alone
+  -h, --help           Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let seed = args.option::<u64>("--seed")?;
+    let mode = args.option::<ProblemMode>("--mode")?;
+    let num_verifiers = args.option::<usize>("--verifiers")?;
+    let count = args.option::<usize>("--count")?.unwrap_or(1);
+    let database = args.option::<String>("--append")?;
+    let skip_trivial = args.flag("--skip-trivial");
+    args.finish()?;
+
+    let mut generator = Generator::new(&catalogue);
+    if let Some(seed) = seed {
+        generator = generator.with_seed(seed);
+    }
+    if let Some(mode) = mode {
+        generator = generator.mode(mode);
+    }
+    if let Some(n) = num_verifiers {
+        generator = generator.num_verifiers(n);
+    }
+    if skip_trivial {
+        generator = generator.skip_trivial();
+    }
+
+    // The seed is printed to regenerate the same games later
+    eprintln!("Seed: {}", generator.seed());
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let record = generator.generate().map_err(|e| format!("{}.", e))?;
+        println!("{}", stringify(record.to_json()));
+        records.push(record);
+    }
+
+    // The games can be added to a database, which is created if needed
+    if let Some(path) = database {
+        let db = if Path::new(&path).exists() { GameDatabase::load(&path) } else { Ok(GameDatabase::default()) };
+        let mut db = db.map_err(|e| e.to_string())?;
+        let added = records.into_iter().filter(|r| db.append(r.clone())).count();
+        db.save(&path).map_err(|e| e.to_string())?;
+        eprintln!("Added {} games to {}", added, path);
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use itertools::Itertools;
+use turing_machine_game::solve::{CommandLineVerifier, Solver, Strategy};
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main hint [OPTIONS] PROBLEM [ANSWER...]
+
+Suggests the next questions to ask, given the answers received so far.
+Answers are written like 241:A=y for a yes of verifier A to the code 241, or 241:A=n for a no.
+
+Options:
+  --catalogue FILE     Reads the cards from a catalogue instead of the official one
+  --input FORMAT       How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  --strategy STRATEGY  rounds or questions, see the solve command [default: rounds]
+  --count N            Number of questions to list [default: 5]
+  -h, --help           Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let strategy = args.option::<Strategy>("--strategy")?.unwrap_or_default();
+    let count = args.option::<usize>("--count")?.unwrap_or(5);
+    let words = args.positional()?;
+    let (problem, answers) = args::split_answers(&words)?;
+    let (problem, _) = args::problem(&catalogue, format, problem)?;
+
+    let mut solver = Solver::<CommandLineVerifier>::from_problem(&problem).map_err(|e| format!("{}.", e))?.strategy(strategy);
+    for answer in answers {
+        solver.answer(answer.code, answer.verifier, answer.accepted).map_err(|e| format!("{}.", answer.rejected(e)))?;
+    }
+
+    match &solver.possible_codes()[..] {
+        [code] => {
+            println!("The code is {}, no more question is needed.", code);
+            return Ok(());
+        }
+        codes if codes.len() <= 10 => println!("{} possible codes: {}", codes.len(), codes.iter().join(", ")),
+        codes => println!("{} possible codes", codes.len()),
+    }
+    println!();
+    println!("code │ score │ verifiers to ask");
+    println!("─────┼───────┼─────────────────");
+    for q in solver.ranked_questions(count) {
+        let elims = q.verifiers.iter().map(|&v| format!("{} {:.1}", "ABCDEF".chars().nth(v).unwrap(), q.eliminations[v]));
+        println!(" {} │{:^7.1}│ {}", q.code, q.score, elims.format(", "));
+    }
+    Ok(())
+}
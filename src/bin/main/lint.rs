@@ -0,0 +1,33 @@
+use colored::Colorize;
+use turing_machine_game::{catalogue::Catalogue, lint::{lint, Severity}};
+use crate::args::Args;
+
+pub const HELP: &str = "Usage: main lint [OPTIONS] [CATALOGUE]
+
+Checks a catalogue for mistakes: duplicate or equivalent laws on a card, laws that accept
+no code or every code, identical cards and unused laws. Checks the official catalogue by default.
+
+Options:
+  -h, --help           Prints this help";
+
+pub fn run(args: Args) -> Result<(), String> {
+    let catalogue = match args.positional()?.as_slice() {
+        [] => Catalogue::builtin().clone(),
+        [path] => Catalogue::load(path).map_err(|e| format!("Could not load catalogue {}: {}", path, e))?,
+        [_, word, ..] => return Err(format!("Unexpected argument {}, see --help for the arguments of the command.", word)),
+    };
+
+    let issues = lint(&catalogue);
+    for issue in issues.iter() {
+        let severity = match issue.severity() {
+            Severity::Warning => "warning".yellow(),
+            Severity::Error => "error".red(),
+        };
+        println!("{}: {}", severity, issue);
+    }
+
+    let num_errors = issues.iter().filter(|i| i.severity() == Severity::Error).count();
+    println!();
+    println!("{} errors, {} warnings", num_errors, issues.len() - num_errors);
+    if num_errors == 0 { Ok(()) } else { Err("The catalogue has errors.".into()) }
+}
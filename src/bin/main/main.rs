@@ -0,0 +1,81 @@
+use std::{env, process::ExitCode};
+use args::Args;
+
+mod analyze;
+mod args;
+mod bench;
+mod coach;
+mod generate;
+mod hint;
+mod lint;
+mod play;
+mod protocol;
+mod replay;
+mod solve;
+mod strategy;
+mod whatif;
+
+const HELP: &str = "Usage: main COMMAND [OPTIONS]
+
+Commands:
+  solve      Solves a game, asking the answers of the verifiers
+  play       Solves a game in a full-screen terminal UI
+  hint       Suggests the next questions to ask, given the answers so far
+  analyze    Lists the valid assignments of laws of a problem
+  generate   Generates new games with a unique solution
+  bench      Compares the solver with the official AI on the games of the database
+  replay     Replays the log of our games
+  coach      Reviews a game played by hand
+  strategy   Exports the strategy of the solver as a decision tree
+  whatif     Shows the consequences of both answers to a question
+  protocol   Drives the solver with a line-based protocol, for other programs
+  lint       Checks a catalogue of cards for mistakes
+
+Run main COMMAND --help for the arguments of a command.";
+
+type Command = (&'static str, fn(Args) -> Result<(), String>, &'static str);
+
+const COMMANDS: [Command; 12] = [
+    ("solve", solve::run, solve::HELP),
+    ("play", play::run, play::HELP),
+    ("hint", hint::run, hint::HELP),
+    ("analyze", analyze::run, analyze::HELP),
+    ("generate", generate::run, generate::HELP),
+    ("bench", bench::run, bench::HELP),
+    ("replay", replay::run, replay::HELP),
+    ("coach", coach::run, coach::HELP),
+    ("strategy", strategy::run, strategy::HELP),
+    ("whatif", whatif::run, whatif::HELP),
+    ("protocol", protocol::run, protocol::HELP),
+    ("lint", lint::run, lint::HELP),
+];
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let name = args.next().unwrap_or_default();
+    let Some((_, run, help)) = COMMANDS.iter().find(|(n, _, _)| *n == name) else {
+        if ["-h", "--help"].contains(&name.as_str()) {
+            println!("{}", HELP);
+            return ExitCode::SUCCESS;
+        }
+        if !name.is_empty() {
+            println!("Unknown command {}.", name);
+            println!();
+        }
+        println!("{}", HELP);
+        return ExitCode::FAILURE;
+    };
+
+    let mut args = Args::new(args);
+    if args.help() {
+        println!("{}", help);
+        return ExitCode::SUCCESS;
+    }
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            println!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
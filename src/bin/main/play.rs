@@ -0,0 +1,233 @@
+use std::io::{self, Write};
+use colored::Colorize;
+use crossterm::{cursor, event::{self, Event, KeyCode, KeyEventKind, KeyModifiers}, execute, queue, terminal::{self, ClearType}};
+use itertools::Itertools;
+use turing_machine_game::{code::Code, problem::Problem, solve::{Answer, Assignment, CommandLineVerifier, Solver, SolverError, Strategy}};
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main play [OPTIONS] PROBLEM
+
+Solves a game in a full-screen terminal UI, showing the laws left for each verifier,
+the questions asked so far and the suggested question.
+
+Keys:
+  y, n                 Answers the question with yes or no
+  u                    Undoes the last answer
+  1-5                  Types another code to ask
+  a-f                  Picks another verifier to ask
+  q, Esc               Quits
+
+Options:
+  --catalogue FILE     Reads the cards from a catalogue instead of the official one
+  --input FORMAT       How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  --strategy STRATEGY  rounds or questions, see the solve command [default: rounds]
+  -h, --help           Prints this help";
+
+// Switches the terminal to a full screen without echo, and puts it back even if the UI fails
+struct Screen;
+
+impl Screen {
+    fn open() -> io::Result<Screen> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+
+    // Raw mode needs explicit carriage returns
+    fn draw(&self, lines: &[String]) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        queue!(stdout, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        write!(stdout, "{}", lines.join("\r\n"))?;
+        stdout.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Game {
+    problem: Problem,
+    candidates: Vec<Vec<Assignment>>, // The laws that each verifier could use before any deduction
+    solver: Solver<CommandLineVerifier>,
+    history: Vec<Solver<CommandLineVerifier>>, // The state of the solver before each answer
+    question: Option<(Code, usize)>,
+    typed: String, // The digits of a code being typed
+    message: String,
+}
+
+fn letter(verifier: usize) -> char {
+    "ABCDEF".chars().nth(verifier).unwrap()
+}
+
+// Explains why a question breaks the rules of the round, for the errors of Solver::check_question
+fn broken_rule(e: &SolverError, code: Code) -> Option<String> {
+    match e {
+        SolverError::UnknownVerifier(v) => Some(format!("There is no verifier {}.", letter(*v))),
+        SolverError::RoundIsFull => Some(format!("The round of {} is full, type another code.", code)),
+        SolverError::VerifierAlreadyAsked(v) => Some(format!("Verifier {} was already asked {} in this round.", letter(*v), code)),
+        _ => None,
+    }
+}
+
+impl Game {
+    fn new(problem: Problem, strategy: Strategy) -> Result<Game, String> {
+        let candidates = Solver::<CommandLineVerifier>::new(&problem).possible_assignments();
+        let solver = Solver::<CommandLineVerifier>::from_problem(&problem).map_err(|e| format!("{}.", e))?.strategy(strategy);
+        let question = solver.next_question();
+        Ok(Game { problem, candidates, solver, history: Vec::new(), question, typed: String::new(), message: String::new() })
+    }
+
+    fn solution(&self) -> Option<Code> {
+        self.solver.has_solution().ok().flatten()
+    }
+
+    fn answer(&mut self, accepted: bool) {
+        let Some((code, verifier)) = self.question else { return };
+        let mut solver = self.solver.clone();
+        let before = solver.num_constraints();
+        if let Err(e) = solver.record_answers(&[Answer { code, verifier, accepted }]) {
+            self.message = broken_rule(&e, code).unwrap_or_else(|| {
+                format!("Verifier {} can't answer {} to {}, given the previous answers.", letter(verifier), if accepted { "yes" } else { "no" }, code)
+            });
+            return;
+        }
+        self.message = format!("Laws eliminated by the answer: {}.", before - solver.num_constraints());
+        self.history.push(std::mem::replace(&mut self.solver, solver));
+        self.question = self.solver.next_question();
+    }
+
+    // Asks the code to the verifier next, unless it breaks the rules of the round
+    fn select(&mut self, code: Code, verifier: usize) {
+        match self.solver.check_question(code, verifier) {
+            Ok(()) => self.question = Some((code, verifier)),
+            Err(e) => self.message = broken_rule(&e, code).unwrap_or_default(),
+        }
+    }
+
+    fn undo(&mut self) {
+        match self.history.pop() {
+            Some(previous) => {
+                self.solver = previous;
+                self.question = self.solver.next_question();
+                self.message = "Last answer undone.".into();
+            }
+            None => self.message = "Nothing to undo.".into(),
+        }
+    }
+
+    // Handles a key, and returns false to quit
+    fn key(&mut self, key: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.message.clear();
+        match key {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('u') => self.undo(),
+            _ if self.solution().is_some() => (),
+            KeyCode::Char('y') => self.answer(true),
+            KeyCode::Char('n') => self.answer(false),
+            KeyCode::Char(c @ '1'..='5') => {
+                self.typed.push(c);
+                if self.typed.len() == 3 {
+                    let code = std::mem::take(&mut self.typed).parse().unwrap();
+                    self.select(code, self.question.map_or(0, |(_, v)| v));
+                }
+            }
+            KeyCode::Backspace => {
+                self.typed.pop();
+            }
+            KeyCode::Char(c @ 'a'..='f') => {
+                let verifier = (c as u8 - b'a') as usize;
+                match self.question {
+                    Some((code, _)) => self.select(code, verifier),
+                    None => self.message = format!("There is no verifier {}.", letter(verifier)),
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{} ─ {} mode ─ round {}, {} questions", "Turing Machine".bold(), self.problem.mode, self.solver.num_rounds(), self.solver.num_questions()),
+            String::new(),
+        ];
+
+        // The laws that were eliminated are crossed out, and the law of a verifier is highlighted once it is known
+        let remaining = self.solver.possible_assignments();
+        for (v, candidates) in self.candidates.iter().enumerate() {
+            let laws = candidates.iter().map(|a| {
+                let text = a.describe(self.problem.mode);
+                if !remaining[v].iter().any(|r| r.card == a.card && r.law.constraint == a.law.constraint) {
+                    text.dimmed().strikethrough().to_string()
+                } else if remaining[v].len() == 1 {
+                    text.green().bold().to_string()
+                } else {
+                    text
+                }
+            });
+            lines.push(format!("{} │ {}", letter(v).to_string().bold(), laws.format("  ")));
+        }
+
+        lines.push(String::new());
+        lines.extend(self.solver.state_lines());
+        lines.push(String::new());
+        let codes = self.solver.possible_codes();
+        if codes.len() <= 20 {
+            lines.push(format!("Possible codes: {}", codes.iter().join(", ")));
+        } else {
+            lines.push(format!("{} possible codes", codes.len()));
+        }
+        lines.push(String::new());
+
+        match (self.solution(), self.question) {
+            (Some(code), _) => {
+                lines.push(format!("Solution: {}", code.to_string().green().bold()));
+                lines.push(String::new());
+                lines.push("[u] undo  [q] quit".into());
+            }
+            (None, Some((code, verifier))) => {
+                if let Some((c, v)) = self.solver.next_question() {
+                    lines.push(format!("Suggested: {} to verifier {}, {:.1} eliminations expected", c, letter(v), self.solver.expected_eliminations_of(c, v)));
+                }
+                let typed = if self.typed.is_empty() { String::new() } else { format!("  (typing {})", self.typed) };
+                lines.push(format!("What does verifier {} answer to {}?{}", letter(verifier).to_string().bold(), code.to_string().bold(), typed));
+                lines.push(String::new());
+                lines.push("[y] yes  [n] no  [u] undo  [1-5] type a code  [a-f] pick a verifier  [q] quit".into());
+            }
+            (None, None) => {
+                lines.push("No question can tell the remaining laws apart.".red().to_string());
+                lines.push(String::new());
+                lines.push("[u] undo  [q] quit".into());
+            }
+        }
+        lines.push(self.message.clone());
+        lines
+    }
+}
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let strategy = args.option::<Strategy>("--strategy")?.unwrap_or_default();
+    let (problem, _) = args::problem(&catalogue, format, &args.positional()?)?;
+    let mut game = Game::new(problem, strategy)?;
+
+    let screen = Screen::open().map_err(|e| format!("The terminal UI needs an interactive terminal: {}.", e))?;
+    loop {
+        screen.draw(&game.lines()).map_err(|e| e.to_string())?;
+        let Event::Key(key) = event::read().map_err(|e| e.to_string())? else { continue };
+        if key.kind == KeyEventKind::Press && !game.key(key.code, key.modifiers) {
+            break;
+        }
+    }
+    drop(screen);
+    if let Some(code) = game.solution() {
+        println!("Solution: {} in {} rounds and {} questions", code, game.solver.num_rounds(), game.solver.num_questions());
+    }
+    Ok(())
+}
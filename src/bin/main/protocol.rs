@@ -0,0 +1,36 @@
+use std::io::{self, BufRead, Write};
+use turing_machine_game::protocol::Session;
+use crate::args::{self, Args};
+
+pub const HELP: &str = "Usage: main protocol [OPTIONS]
+
+Drives the solver with a line-based protocol on stdin and stdout, for other programs.
+Every command gets a single line of response, which starts with ok, error, question, solution or state.
+
+Commands:
+  load PROBLEM         Loads a problem given as a mode followed by card ids, or as a share code
+  next                 Gives the next question to ask, like question 241:A, or solution 241 once the code is known
+  answer 241:A=y       Records the answer of a verifier to a code
+  state                Gives the number of rounds, questions and possible codes, and the constraints left per verifier
+  undo                 Cancels the last answer
+  quit                 Ends the session
+
+Options:
+  --catalogue FILE     Reads the cards from a catalogue instead of the official one
+  -h, --help           Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let mut session = Session::new(args::catalogue(&mut args)?);
+    args.finish()?;
+
+    // Each response is flushed right away, since the other program waits for it
+    let mut stdout = io::stdout();
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        writeln!(stdout, "{}", session.handle(&line)).and_then(|_| stdout.flush()).map_err(|e| e.to_string())?;
+        if session.is_finished() {
+            break;
+        }
+    }
+    Ok(())
+}
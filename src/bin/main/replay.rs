@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use colored::Colorize;
+use turing_machine_game::{bench::Outcome, catalogue::Catalogue, games::{GameDatabase, GAMES_PATH}, playlog::{self, Replay, Score, PLAY_LOG_PATH}};
+use crate::args::Args;
+
+pub const HELP: &str = "Usage: main replay [FILE]
+
+Replays the log of the games that we played ourselves against every possible assignment of laws,
+and compares the solver with the player and the official AI. Since the laws of our games are unknown,
+the solver wins or loses when it needs fewer or more questions in most of the assignments.
+
+Arguments:
+  FILE                 The log of the games [default: data/games.txt]
+
+Options:
+  -h, --help           Prints this help";
+
+// The outcome against a player, with the share of the worlds that decided it when the laws are unknown
+fn colored(replay: &Replay, score: Score) -> colored::ColoredString {
+    let outcome = replay.outcome_against(score);
+    let (better, worse) = replay.shares_against(score);
+    let share = match outcome {
+        Outcome::Win => better,
+        Outcome::Loss => worse,
+        _ => 1.0 - better - worse,
+    };
+    let text = match replay {
+        Replay::Official(_) => outcome.to_string(),
+        Replay::AllWorlds(_) => format!("{} {:.0}%", outcome, 100.0 * share),
+    };
+    let text = format!("{:^10}", text);
+    match outcome {
+        Outcome::Win => text.green(),
+        Outcome::Tie => text.yellow(),
+        _ => text.red(),
+    }
+}
+
+pub fn run(args: Args) -> Result<(), String> {
+    // Replays the log of our games, or the one whose path is given as argument
+    let path = match &args.positional()?[..] {
+        [] => PLAY_LOG_PATH.to_string(),
+        [path] => path.clone(),
+        _ => return Err("Only one log can be replayed at a time.".into()),
+    };
+    let log = playlog::load(&path).map_err(|e| e.to_string())?;
+    // The official laws are used for the games that are also in the database
+    let db = GameDatabase::load(GAMES_PATH).unwrap_or_default();
+
+    println!("   id    │   mode    │  me  │  AI  │ logged │ solver │ worst │   vs me  │   vs AI");
+    println!("─────────┼───────────┼──────┼──────┼────────┼────────┼───────┼──────────┼──────────");
+    let mut against_me: BTreeMap<Outcome, usize> = BTreeMap::new();
+    let mut against_ai: BTreeMap<Outcome, usize> = BTreeMap::new();
+    for record in log.iter() {
+        let replay = match record.replay(&db, Catalogue::builtin()) {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{}", format!("Game {} is invalid: {}", record.id, e).on_red());
+                continue;
+            }
+        };
+        let solver = match replay {
+            Replay::Official(s) => s.to_string(),
+            Replay::AllWorlds(_) => format!("{:.1}/{:.1}", replay.mean_rounds(), replay.mean_questions()),
+        };
+        *against_me.entry(replay.outcome_against(record.me)).or_default() += 1;
+        *against_ai.entry(replay.outcome_against(record.ai)).or_default() += 1;
+        println!("{:^9}│{:^11}│{:^6}│{:^6}│{:^8}│{:^8}│{:^7}│{}│{}", record.id, record.mode.to_string(), record.me.to_string(),
+            record.ai.to_string(), record.my_ai.to_string(), solver, replay.worst_questions(), colored(&replay, record.me), colored(&replay, record.ai));
+    }
+
+    // The laws of our games are unknown, so the solver is compared with its number of questions over every possible laws
+    let count = |outcomes: &BTreeMap<Outcome, usize>, o| outcomes.get(&o).copied().unwrap_or(0);
+    println!();
+    for (name, outcomes) in [("our players", &against_me), ("the official AI", &against_ai)] {
+        println!("Against {}: {} wins, {} ties, {} losses", name,
+            count(outcomes, Outcome::Win), count(outcomes, Outcome::Tie), count(outcomes, Outcome::Loss));
+    }
+    Ok(())
+}
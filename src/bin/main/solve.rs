@@ -0,0 +1,152 @@
+use itertools::Itertools;
+use turing_machine_game::{analyze::analyze, catalogue::Catalogue, problem::{Problem, ProblemMode}, solve::{Answer, Assignment, CommandLineVerifier, Solver, SolverError, SolverVerbosity, Strategy}};
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main solve [OPTIONS] PROBLEM
+
+Solves a game, asking the answers of the verifiers on the command line.
+The problem is a mode followed by card ids, like normal 16 31 46 48, or a share code.
+
+Options:
+  --catalogue FILE     Reads the cards from a catalogue instead of the official one
+  --input FORMAT       How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  --verbosity LEVEL    quiet, normal, or verbose to explain each step and list the best questions [default: normal]
+  --strategy STRATEGY  rounds to ask each code to up to three verifiers, or questions to ask one verifier per round [default: rounds]
+  --answer ANSWER      An answer already known, like 241:A=y for a yes of verifier A to the code 241.
+                       Can be repeated, and consecutive answers to the same code make a round.
+  --deduction          Only checks whether the code can be found without asking any question
+  -h, --help           Prints this help";
+
+// Prints the card and the law used by a verifier
+fn print_assignment(problem: &Problem, a: &Assignment) {
+    let letter = "ABCDEF".chars().nth(a.verifier).unwrap();
+    let card = |i: usize| problem.cards[i].id.map_or(format!("#{}", i + 1), |id| id.to_string());
+    match problem.mode {
+        ProblemMode::Normal => println!("{}: {}", letter, a.law.label()),
+        ProblemMode::Extreme => println!("{}: card {} (card {} is fake), {}", letter, card(a.card), card(a.card ^ 1), a.law.label()),
+        ProblemMode::Nightmare => println!("{}: card {}, {}", letter, card(a.card), a.law.label()),
+    }
+}
+
+// Compares the laws found by the solver with the ones given in the share code or the database
+fn check_known_laws(catalogue: &Catalogue, assignment: &[Assignment], laws: &[u8]) {
+    let mut all_match = true;
+    for (a, &id) in assignment.iter().zip(laws) {
+        if catalogue.law(id).is_ok_and(|l| !l.is_equivalent_to(&a.law)) {
+            let letter = "ABCDEF".chars().nth(a.verifier).unwrap();
+            println!("According to the input, verifier {} uses law {} instead.", letter, id);
+            all_match = false;
+        }
+    }
+    if all_match {
+        println!("These are the laws given with the problem.");
+    }
+}
+
+// Reports whether the code can be found without asking any question
+fn print_deduction(problem: &Problem) -> Result<(), String> {
+    let analysis = analyze(problem).map_err(|e| format!("{}.", e))?;
+    println!();
+    match analysis.solution() {
+        Some(code) => println!("The code {} is determined by deduction alone, no question is needed.", code),
+        None => {
+            let codes = analysis.codes();
+            println!("The deductions leave {} possible assignments of laws and {} possible codes:", analysis.worlds.len(), codes.len());
+            println!("{}", codes.iter().map(|(c, _)| c.to_string()).join(", "));
+        }
+    }
+    Ok(())
+}
+
+// Applies the answers given with --answer, which make rounds like in Solver::record_answers
+fn record_answers(solver: &mut Solver<CommandLineVerifier>, answers: &[Answer]) -> Result<(), String> {
+    match solver.eliminate().and_then(|_| solver.record_answers(answers)) {
+        Ok(()) => Ok(()),
+        Err(SolverError::UnknownVerifier(v)) => Err(format!("Verifier {} of the answers is not part of the game.", "ABCDEF".chars().nth(v).unwrap_or('?'))),
+        Err(SolverError::RoundIsFull) => Err(format!("The answers given with --answer ask a code to more than {} verifiers in a round.", solver.questions_per_round())),
+        Err(SolverError::VerifierAlreadyAsked(v)) => Err(format!("Verifier {} of the answers is asked twice in a round.", "ABCDEF".chars().nth(v).unwrap_or('?'))),
+        Err(_) => Err("The answers given with --answer contradict each other, or the problem is ill-defined.".into()),
+    }
+}
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let verbosity = args.option::<SolverVerbosity>("--verbosity")?.unwrap_or(SolverVerbosity::Normal);
+    let strategy = args.option::<Strategy>("--strategy")?.unwrap_or_default();
+    let answers = args.options::<Answer>("--answer")?;
+    let deduction_only = args.flag("--deduction");
+    let (problem, known_laws) = args::problem(&catalogue, format, &args.positional()?)?;
+
+    print!("{}", problem);
+    if let Ok(code) = problem.to_share_code(None) {
+        println!("Share code: {}", code);
+    }
+    if deduction_only {
+        return print_deduction(&problem);
+    }
+
+    let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(verbosity).strategy(strategy);
+
+    // The solver continues from the answers of the rounds that were played without it
+    record_answers(&mut solver, &answers)?;
+    match solver.solve() {
+        Ok(sol) => {
+            println!();
+            println!("Found solution: {}", sol.code);
+            println!("Number of rounds: {}", solver.num_rounds());
+            println!("Number of questions: {}", solver.num_questions());
+            println!();
+            println!("Laws of the verifiers:");
+            for a in sol.assignment.iter() {
+                print_assignment(&problem, a);
+            }
+            if let Some(laws) = known_laws {
+                check_known_laws(&catalogue, &sol.assignment, &laws);
+            }
+        }
+        Err(SolverError::Impossible(vs)) => {
+            let letters = "ABCDEF".chars().collect_vec();
+            println!();
+            println!("Verifiers {} are invalid because all their constraints are impossible.", vs.iter().map(|v| letters[*v]).join(", "));
+            println!("You might have entered a wrong value, or the problem is ill-defined.");
+        }
+        Err(SolverError::MultipleSolutions(sols)) => {
+            println!();
+            println!("The set of cards leads to several solutions: {}", sols.iter().map(|s| format!("{}", s)).join(", "));
+            println!("You might have entered a wrong value, or the problem is ill-defined.");
+        }
+        Err(SolverError::UnknownVerifier(v)) => {
+            println!();
+            println!("Verifier {} is not part of the game.", "ABCDEF".chars().nth(v).unwrap_or('?'));
+        }
+        Err(SolverError::RoundIsFull | SolverError::VerifierAlreadyAsked(_)) => unreachable!("The solver asks its own rounds by the rules"),
+    }
+
+    println!();
+    solver.print_state();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use turing_machine_game::solve::{Answer, CommandLineVerifier, Solver, SolverVerbosity};
+    use crate::args::Args;
+    use super::record_answers;
+
+    #[test]
+    pub fn answers_of_the_command_line_make_rounds() {
+        let problem = "normal 4 9 11 14".parse().unwrap();
+        let answers = |words: &[&str]| Args::new(words.iter().map(|w| w.to_string())).options::<Answer>("--answer").unwrap();
+        let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(SolverVerbosity::None);
+        assert!(record_answers(&mut solver, &answers(&["--answer", "445:C=y", "--answer", "445:A=n", "--answer", "445:B=y"])).is_ok());
+        assert_eq!((solver.num_rounds(), solver.num_questions()), (1, 3));
+        assert_eq!(solver.possible_codes().len(), 1);
+
+        let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(SolverVerbosity::None);
+        let full = answers(&["--answer", "445:C=y", "--answer", "445:A=n", "--answer", "445:B=y", "--answer", "445:D=n"]);
+        assert_eq!(record_answers(&mut solver, &full).unwrap_err(), "The answers given with --answer ask a code to more than 3 verifiers in a round.");
+        assert_eq!(record_answers(&mut solver, &answers(&["--answer", "445:C=y", "--answer", "445:C=y"])).unwrap_err(), "Verifier C of the answers is asked twice in a round.");
+        assert_eq!(solver.num_questions(), 0);
+    }
+}
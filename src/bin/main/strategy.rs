@@ -0,0 +1,33 @@
+use std::fs;
+use turing_machine_game::strategy::DecisionTree;
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main strategy [OPTIONS] PROBLEM
+
+Exports the whole strategy of the solver for a problem as a decision tree, printed as a Graphviz graph.
+
+Options:
+  --catalogue FILE  Reads the cards from a catalogue instead of the official one
+  --input FORMAT    How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  --json            Prints the tree as JSON instead
+  --output FILE     Saves the tree to a file instead of printing it
+  -h, --help        Prints this help";
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let output = args.option::<String>("--output")?;
+    let as_json = args.flag("--json");
+    let (problem, _) = args::problem(&catalogue, format, &args.positional()?)?;
+    let tree = DecisionTree::of(&problem).map_err(|e| format!("{}.", e))?;
+
+    let text = if as_json { json::stringify_pretty(tree.to_json(), 2) + "\n" } else { tree.to_dot() };
+    match output {
+        Some(path) => {
+            fs::write(&path, text).map_err(|e| format!("Could not write {}: {}", path, e))?;
+            println!("The strategy covers {} codes in at most {} questions.", tree.solutions().len(), tree.max_questions());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use itertools::Itertools;
+use turing_machine_game::{problem::Problem, solve::{parse_question, CommandLineVerifier, Solver}, whatif::{Hypothesis, WhatIf}};
+use crate::args::{self, Args, InputFormat};
+
+pub const HELP: &str = "Usage: main whatif [OPTIONS] PROBLEM [ANSWER...] QUESTION
+
+Shows the laws and codes left after each answer to a question, before asking it.
+Answers are written like 241:A=y for a yes of verifier A to the code 241, and the question like 241:B.
+
+Options:
+  --catalogue FILE  Reads the cards from a catalogue instead of the official one
+  --input FORMAT    How the problem is written: auto, text, share, game (a hash of the database) or json [default: auto]
+  -h, --help        Prints this help";
+
+fn print_hypothesis(problem: &Problem, answer: &str, hypothesis: &Option<Hypothesis>) {
+    println!();
+    let Some(h) = hypothesis else {
+        println!("If the answer is {}: impossible, this answer contradicts the previous ones.", answer);
+        return;
+    };
+    println!("If the answer is {}: {} constraints eliminated", answer, h.eliminated);
+    for (v, laws) in h.laws.iter().enumerate() {
+        println!("{}: {}", "ABCDEF".chars().nth(v).unwrap(), laws.iter().map(|a| a.describe(problem.mode)).join(", "));
+    }
+    match &h.codes[..] {
+        [code] => println!("The code would be {}.", code),
+        codes => println!("{} possible codes: {}", codes.len(), codes.iter().join(", ")),
+    }
+}
+
+pub fn run(mut args: Args) -> Result<(), String> {
+    let catalogue = args::catalogue(&mut args)?;
+    let format = args.option::<InputFormat>("--input")?.unwrap_or_default();
+    let words = args.positional()?;
+
+    // The question comes last, after the problem and the answers already given
+    let Some((question, words)) = words.split_last() else {
+        return Err("Please pass the problem, the answers already given and the question, like 241:B.".into());
+    };
+    let (code, verifier) = parse_question(question).map_err(|e| format!("{}.", e))?;
+    let (problem, answers) = args::split_answers(words)?;
+    let (problem, _) = args::problem(&catalogue, format, problem)?;
+
+    // The answers are applied to the solver, and both answers to the question are tried on copies of it
+    let mut solver = Solver::<CommandLineVerifier>::from_problem(&problem).map_err(|e| format!("{}.", e))?;
+    for answer in answers {
+        solver.answer(answer.code, answer.verifier, answer.accepted).map_err(|e| format!("{}.", answer.rejected(e)))?;
+    }
+    let what_if = WhatIf::of(&solver, code, verifier).map_err(|e| format!("{}.", e))?;
+
+    println!("Asking {} to verifier {}:", what_if.code, "ABCDEF".chars().nth(what_if.verifier).unwrap());
+    print_hypothesis(&problem, "yes", &what_if.yes);
+    print_hypothesis(&problem, "no", &what_if.no);
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path, sync::OnceLock};
+use itertools::Itertools;
+use json::JsonValue;
+use crate::{cards::{card_from_id, constraint_from_id, NUM_CARDS, NUM_LAWS}, error::Error, expr::ParseError, law::Law, problem::Card};
+
+// The version of the catalogue file format written by this library
+pub const CATALOGUE_VERSION: u32 = 1;
+
+// The set of laws and cards that problems can be built from
+#[derive(Clone, PartialEq, Default)]
+pub struct Catalogue {
+    laws: BTreeMap<u8, Law>,
+    cards: BTreeMap<u8, Card>,
+}
+
+#[derive(Debug)]
+pub enum CatalogueError {
+    Io(std::io::Error),
+    Json(json::Error),
+    UnsupportedVersion(u32),
+    Malformed(String),
+    InvalidDefinition { definition: String, error: ParseError },
+    UnknownLaw { card: u8, law: u8 },
+}
+
+impl Catalogue {
+    // The catalogue of the official game, as defined by the tables in the cards module
+    pub fn builtin() -> &'static Catalogue {
+        static BUILTIN: OnceLock<Catalogue> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut catalogue = Catalogue::default();
+            for id in 1..=NUM_LAWS {
+                catalogue.insert_law(id, constraint_from_id(id).expect("The built-in laws are numbered contiguously"));
+            }
+            for id in 1..=NUM_CARDS {
+                catalogue.insert_card(id, card_from_id(id).expect("The built-in cards are numbered contiguously"));
+            }
+            catalogue
+        })
+    }
+
+    pub fn law(&self, id: u8) -> Result<&Law, Error> {
+        self.laws.get(&id).ok_or(Error::UnknownLaw(id))
+    }
+
+    pub fn card(&self, id: u8) -> Result<&Card, Error> {
+        self.cards.get(&id).ok_or(Error::UnknownCard(id))
+    }
+
+    pub fn laws(&self) -> impl Iterator<Item = &Law> {
+        self.laws.values()
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.cards.values()
+    }
+
+    // Adds a law to the catalogue, replacing the one that had the same id
+    pub fn insert_law(&mut self, id: u8, law: Law) {
+        self.laws.insert(id, law.with_id(id));
+    }
+
+    // Adds a card to the catalogue, replacing the one that had the same id
+    pub fn insert_card(&mut self, id: u8, card: Card) {
+        self.cards.insert(id, card.with_id(id));
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Catalogue, CatalogueError> {
+        Self::from_json(&fs::read_to_string(path).map_err(CatalogueError::Io)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CatalogueError> {
+        fs::write(path, self.to_json()).map_err(CatalogueError::Io)
+    }
+
+    pub fn from_json(s: &str) -> Result<Catalogue, CatalogueError> {
+        let malformed = |msg: String| CatalogueError::Malformed(msg);
+        let parse_law = |definition: &str| definition.parse::<Law>()
+            .map_err(|error| CatalogueError::InvalidDefinition { definition: definition.into(), error });
+
+        let root = json::parse(s).map_err(CatalogueError::Json)?;
+        let version = root["version"].as_u32().ok_or_else(|| malformed("Missing catalogue version".into()))?;
+        if version > CATALOGUE_VERSION {
+            return Err(CatalogueError::UnsupportedVersion(version));
+        }
+        if !root["laws"].is_array() || !root["cards"].is_array() {
+            return Err(malformed("The catalogue must contain a list of laws and a list of cards".into()));
+        }
+
+        let mut catalogue = Catalogue::default();
+        for entry in root["laws"].members() {
+            let id = entry["id"].as_u8().ok_or_else(|| malformed(format!("Invalid law id in {}", entry)))?;
+            let definition = entry["definition"].as_str().ok_or_else(|| malformed(format!("Missing definition for law {}", id)))?;
+            let law = parse_law(definition)?;
+            let law = match entry["name"].as_str() {
+                Some(name) => law.with_name(name.into()),
+                None => law,
+            };
+            if catalogue.laws.contains_key(&id) {
+                return Err(malformed(format!("Law {} is defined twice", id)));
+            }
+            catalogue.insert_law(id, law);
+        }
+
+        for entry in root["cards"].members() {
+            let id = entry["id"].as_u8().ok_or_else(|| malformed(format!("Invalid card id in {}", entry)))?;
+            // Laws are referenced by id, or defined inline when they have no official number
+            let laws = entry["laws"].members().map(|l| match (l.as_u8(), l.as_str()) {
+                (Some(law), _) => catalogue.law(law).cloned().map_err(|_| CatalogueError::UnknownLaw { card: id, law }),
+                (_, Some(definition)) => parse_law(definition),
+                _ => Err(malformed(format!("Invalid law {} on card {}", l, id))),
+            }).collect::<Result<Vec<_>, _>>()?;
+            if laws.is_empty() {
+                return Err(malformed(format!("Card {} has no laws", id)));
+            }
+            if catalogue.cards.contains_key(&id) {
+                return Err(malformed(format!("Card {} is defined twice", id)));
+            }
+            catalogue.insert_card(id, Card::new(laws));
+        }
+        Ok(catalogue)
+    }
+
+    // Writes the catalogue with one law or card per line, so that it can easily be edited by hand
+    pub fn to_json(&self) -> String {
+        let laws = self.laws.iter().map(|(&id, law)| {
+            let mut obj = json::object! { id: id, definition: law.expr.to_string() };
+            if law.name != law.expr.to_string() {
+                obj["name"] = law.name.clone().into();
+            }
+            json::stringify(obj)
+        });
+        let cards = self.cards.iter().map(|(&id, card)| {
+            let laws: Vec<JsonValue> = card.laws.iter().map(|law| match law.id {
+                Some(l) if self.law(l) == Ok(law) => l.into(),
+                _ => law.expr.to_string().into(),
+            }).collect();
+            json::stringify(json::object! { id: id, laws: laws })
+        });
+        format!("{{\n  \"version\": {},\n  \"laws\": [\n    {}\n  ],\n  \"cards\": [\n    {}\n  ]\n}}\n",
+            CATALOGUE_VERSION, laws.format(",\n    "), cards.format(",\n    "))
+    }
+}
+
+impl Display for CatalogueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogueError::Io(e) => write!(f, "Could not read the catalogue: {}", e),
+            CatalogueError::Json(e) => write!(f, "The catalogue is not valid JSON: {}", e),
+            CatalogueError::UnsupportedVersion(v) =>
+                write!(f, "Catalogue version {} is not supported (latest is {})", v, CATALOGUE_VERSION),
+            CatalogueError::Malformed(msg) => write!(f, "{}", msg),
+            CatalogueError::InvalidDefinition { definition, error } =>
+                write!(f, "Invalid law definition '{}': {}", definition, error),
+            CatalogueError::UnknownLaw { card, law } => write!(f, "Card {} uses law {} which is not defined", card, law),
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+#[cfg(test)]
+mod tests {
+    use super::Catalogue;
+
+    #[test]
+    pub fn shipped_catalogue_matches_builtin() {
+        let catalogue = Catalogue::load("data/catalogue.json").unwrap();
+        assert!(&catalogue == Catalogue::builtin());
+        assert!(Catalogue::from_json(&catalogue.to_json()).unwrap() == catalogue);
+    }
+}
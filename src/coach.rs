@@ -0,0 +1,79 @@
+use crate::{code::Code, error::Error, problem::Problem, solve::{Answer, CommandLineVerifier, Solver}};
+
+// The review of a question asked by a player, compared with the question the solver would have asked instead
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestionReview {
+    pub answer: Answer,
+    pub expected: f32, // The expected number of constraints eliminated by the answer alone
+    pub eliminated: usize, // The constraints eliminated by the answer alone
+    pub deduced: usize, // The constraints eliminated by the deductions that followed
+    pub best: Option<(Code, usize, f32)>, // The question of the solver, with its verifier and expected eliminations
+    pub known_code: Option<Code>, // The code, if it could already be deduced before the question
+}
+
+// The review of a whole game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    pub questions: Vec<QuestionReview>,
+    pub solution: Option<Code>, // The code, if the answers are enough to deduce it
+}
+
+impl QuestionReview {
+    // The answer taught nothing, not even through deductions
+    pub fn is_wasted(&self) -> bool {
+        self.eliminated + self.deduced == 0
+    }
+}
+
+impl Review {
+    // The number of questions that were asked although the code was already known
+    pub fn num_unneeded(&self) -> usize {
+        self.questions.iter().filter(|q| q.known_code.is_some()).count()
+    }
+
+    pub fn num_wasted(&self) -> usize {
+        self.questions.iter().filter(|q| q.is_wasted()).count()
+    }
+}
+
+// Replays the answers of a game through the solver, and reviews every question
+pub fn review(problem: &Problem, answers: &[Answer]) -> Result<Review, Error> {
+    let mut solver = Solver::<CommandLineVerifier>::from_problem(problem)?;
+    let known_code = |solver: &Solver<CommandLineVerifier>| match solver.possible_codes()[..] {
+        [code] => Some(code),
+        _ => None,
+    };
+
+    let mut questions = Vec::with_capacity(answers.len());
+    for &answer in answers {
+        let expected = solver.expected_eliminations_of(answer.code, answer.verifier);
+        let best = solver.suggestion().map(|(c, v)| (c, v, solver.expected_eliminations_of(c, v)));
+        let known_code = known_code(&solver);
+        let eliminated = solver.eliminations_of(answer.code, answer.verifier, answer.accepted);
+        let total = solver.answer(answer.code, answer.verifier, answer.accepted).map_err(|e| answer.rejected(e))?;
+        questions.push(QuestionReview { answer, expected, eliminated, deduced: total - eliminated, best, known_code });
+    }
+    Ok(Review { solution: known_code(&solver), questions })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{code::Code, problem::Problem, solve::Answer};
+    use super::review;
+
+    #[test]
+    pub fn review_of_a_game() {
+        let problem: Problem = "normal 4 9 11 14".parse().unwrap();
+        let answers = ["241:A=y", "241:B=y"].map(|a| a.parse().unwrap());
+        let game = review(&problem, &answers).unwrap();
+        let code: Code = "241".parse().unwrap();
+        assert_eq!(game.solution, Some(code));
+        assert!(!game.questions[0].is_wasted() && game.questions[0].known_code.is_none());
+        assert!(game.questions[1].is_wasted() && game.questions[1].known_code == Some(code));
+        assert_eq!((game.num_wasted(), game.num_unneeded()), (1, 1));
+
+        let contradiction = ["241:A=y", "221:A=y"].map(|a| a.parse().unwrap());
+        assert!(review(&problem, &contradiction).is_err());
+        assert!("241:G=y".parse::<Answer>().is_err());
+    }
+}
@@ -1,7 +1,8 @@
-use std::{fmt::Display, ops::Index};
+use std::{fmt::Display, ops::Index, str::FromStr};
 use itertools::{iproduct, Itertools};
+use crate::error::Error;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Symbol {
     Triangle, Square, Circle
 }
@@ -28,7 +29,7 @@ impl Display for Symbol {
 
 pub type Digit = u8;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Code {
     digits: [Digit; 3],
 }
@@ -79,6 +80,18 @@ impl Index<u8> for Code {
     }
 }
 
+impl FromStr for Code {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<_> = s.chars().map(|c| c.to_digit(10).filter(|d| (1..=5).contains(d))).collect();
+        match digits[..] {
+            [Some(a), Some(b), Some(c)] => Ok(Code::new(a as Digit, b as Digit, c as Digit)),
+            _ => Err(Error::InvalidCode(s.into())),
+        }
+    }
+}
+
 impl Display for Code {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}{}{}", self[0], self[1], self[2])
@@ -0,0 +1,186 @@
+use itertools::Itertools;
+use crate::{error::Error, games::Difficulty, problem::{Problem, ProblemMode}, solve::{CommandLineVerifier, Solver}};
+
+// Maximum number of worlds that are solved to estimate the number of questions
+const MAX_SAMPLED_WORLDS: usize = 16;
+
+// The model calibrated on data/games.json, which can be refitted with `main bench --difficulty`
+pub const BUILTIN_MODEL: DifficultyModel = DifficultyModel {
+    weights: [0.8979422, 0.25422364, -0.17528485, -1.0643904],
+    thresholds: [(1.8154179, 2.7468843), (2.2805836, 2.5291495), (1.9652772, 2.3373432)],
+};
+
+// The properties of a problem that make it hard to solve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Features {
+    pub mode: ProblemMode,
+    pub num_verifiers: usize,
+    pub num_worlds: usize, // Number of valid assignments of laws to the verifiers
+    pub constraints_per_card: f32,
+    pub deductions: f32, // Fraction of the constraints eliminated before any question
+    pub expected_questions: f32, // Average number of questions asked by the solver
+}
+
+impl Features {
+    pub fn of(problem: &Problem) -> Result<Features, Error> {
+        let num_constraints = Solver::<CommandLineVerifier>::new(problem).num_constraints();
+        let solver = Solver::<CommandLineVerifier>::from_problem(problem)?;
+        let worlds: Vec<_> = solver.worlds().collect();
+
+        // The solver is run on evenly spaced worlds, starting from the deductions that were already made
+        let step = worlds.len().div_ceil(MAX_SAMPLED_WORLDS);
+        let questions: Vec<_> = worlds.iter().step_by(step).map(|w| {
+            let mut solver = solver.clone().automatic(w.clone());
+            solver.solve().map_or(0, |_| solver.num_questions())
+        }).collect();
+
+        Ok(Features {
+            mode: problem.mode,
+            num_verifiers: problem.num_verifiers(),
+            num_worlds: worlds.len(),
+            constraints_per_card: problem.cards.iter().map(|c| c.laws.len()).sum::<usize>() as f32 / problem.cards.len() as f32,
+            deductions: 1.0 - solver.num_constraints() as f32 / num_constraints as f32,
+            expected_questions: questions.iter().sum::<usize>() as f32 / questions.len() as f32,
+        })
+    }
+
+    // The values that are weighted by the model. The worlds and questions are counted per verifier,
+    // so that games with more verifiers don't look harder only because of their size.
+    fn values(&self) -> [f32; 4] {
+        let n = self.num_verifiers as f32;
+        [self.constraints_per_card, (self.num_worlds as f32).log2() / n, self.expected_questions / n, self.deductions]
+    }
+}
+
+// Scores problems with a weighted sum of their features, and compares the score to thresholds
+// that depend on the mode, since the official difficulties are relative to the mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyModel {
+    pub weights: [f32; 4], // Weights of the constraints per card, worlds, questions and deductions
+    pub thresholds: [(f32, f32); 3], // Scores from which games are standard and hard, for every mode
+}
+
+fn mode_index(mode: ProblemMode) -> usize {
+    match mode {
+        ProblemMode::Normal => 0,
+        ProblemMode::Extreme => 1,
+        ProblemMode::Nightmare => 2,
+    }
+}
+
+fn classify(score: f32, (standard, hard): (f32, f32)) -> Difficulty {
+    if score >= hard { Difficulty::Hard } else if score >= standard { Difficulty::Standard } else { Difficulty::Easy }
+}
+
+// Finds the thresholds that classify the most scores correctly
+fn fit_thresholds(scores: &[(f32, Difficulty)]) -> ((f32, f32), usize) {
+    let sorted = scores.iter().map(|(s, _)| *s).sorted_by(f32::total_cmp).dedup().collect_vec();
+    let candidates = std::iter::once(f32::NEG_INFINITY)
+        .chain(sorted.iter().tuple_windows().map(|(a, b)| (a + b) / 2.0))
+        .chain(std::iter::once(f32::INFINITY))
+        .collect_vec();
+    candidates.iter().tuple_combinations().chain(candidates.iter().map(|t| (t, t)))
+        .map(|(&s, &h)| ((s, h), scores.iter().filter(|(score, d)| classify(*score, (s, h)) == *d).count()))
+        .max_by_key(|(_, correct)| *correct)
+        .unwrap_or(((0.0, 0.0), 0))
+}
+
+// Solves the normal equations of a least squares fit by Gauss-Jordan elimination. A small ridge term keeps
+// the system solvable when a feature doesn't vary, or when two features are proportional.
+fn least_squares(rows: &[Vec<f64>], targets: &[f64]) -> Vec<f64> {
+    let n = rows.first().map_or(0, |r| r.len());
+    let mut m = vec![vec![0.0; n + 1]; n];
+    for (row, y) in rows.iter().zip(targets) {
+        for i in 0..n {
+            for j in 0..n {
+                m[i][j] += row[i] * row[j];
+            }
+            m[i][n] += row[i] * y;
+        }
+    }
+    for (i, r) in m.iter_mut().enumerate() {
+        r[i] += 1e-6;
+    }
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs())).unwrap();
+        m.swap(col, pivot);
+        let p = m[col].clone();
+        for (i, r) in m.iter_mut().enumerate() {
+            if i != col {
+                let factor = r[col] / p[col];
+                r.iter_mut().zip(p.iter()).for_each(|(v, pv)| *v -= factor * pv);
+            }
+        }
+    }
+    m.iter().enumerate().map(|(i, r)| r[n] / r[i]).collect()
+}
+
+impl DifficultyModel {
+    pub fn score(&self, f: &Features) -> f32 {
+        self.weights.iter().zip(f.values()).map(|(w, v)| w * v).sum()
+    }
+
+    pub fn estimate(&self, f: &Features) -> Difficulty {
+        classify(self.score(f), self.thresholds[mode_index(f.mode)])
+    }
+
+    // Fits the model on games whose difficulty is known. The weights are fitted by least squares on the
+    // difficulties taken as 0, 1 and 2, with an offset per mode, and the thresholds are then picked for every mode.
+    pub fn calibrate(samples: &[(Features, Difficulty)]) -> DifficultyModel {
+        let rows = samples.iter().map(|(f, _)| {
+            let offsets = (0..3).map(|m| if m == mode_index(f.mode) { 1.0 } else { 0.0 });
+            f.values().iter().map(|&v| v as f64).chain(offsets).collect_vec()
+        }).collect_vec();
+        let targets = samples.iter().map(|(_, d)| *d as usize as f64).collect_vec();
+        let fitted = least_squares(&rows, &targets);
+
+        let mut model = DifficultyModel { weights: [0.0; 4], thresholds: [(0.0, 0.0); 3] };
+        model.weights.iter_mut().zip(fitted).for_each(|(w, f)| *w = f as f32);
+        for mode in [ProblemMode::Normal, ProblemMode::Extreme, ProblemMode::Nightmare] {
+            let scores = samples.iter().filter(|(f, _)| f.mode == mode).map(|(f, d)| (model.score(f), *d)).collect_vec();
+            model.thresholds[mode_index(mode)] = fit_thresholds(&scores).0;
+        }
+        model
+    }
+
+    // The fraction of games whose difficulty is estimated correctly
+    pub fn accuracy(&self, samples: &[(Features, Difficulty)]) -> f32 {
+        samples.iter().filter(|(f, d)| self.estimate(f) == *d).count() as f32 / samples.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{games::{Difficulty, GameDatabase, GAMES_PATH}, problem::ProblemMode};
+    use super::{DifficultyModel, Features, BUILTIN_MODEL};
+
+    #[test]
+    pub fn calibration_separates_difficulties() {
+        let features = |mode, constraints_per_card| Features {
+            mode, num_verifiers: 4, num_worlds: 8, constraints_per_card, deductions: 0.5, expected_questions: 4.0,
+        };
+        let samples = [
+            (features(ProblemMode::Normal, 2.5), Difficulty::Easy),
+            (features(ProblemMode::Normal, 3.0), Difficulty::Standard),
+            (features(ProblemMode::Normal, 4.0), Difficulty::Hard),
+            (features(ProblemMode::Nightmare, 3.0), Difficulty::Easy),
+            (features(ProblemMode::Nightmare, 4.5), Difficulty::Hard),
+        ];
+        let model = DifficultyModel::calibrate(&samples);
+        assert_eq!(model.accuracy(&samples), 1.0);
+        assert_eq!(model.estimate(&features(ProblemMode::Normal, 4.5)), Difficulty::Hard);
+        assert_eq!(model.estimate(&features(ProblemMode::Nightmare, 2.0)), Difficulty::Easy);
+    }
+
+    // The games with four verifiers are quick to analyse, and all but one of them are estimated correctly
+    #[test]
+    pub fn builtin_model_matches_the_database() {
+        let db = GameDatabase::load(GAMES_PATH).unwrap();
+        let samples: Vec<_> = db.records().iter().filter(|r| r.laws.len() == 4)
+            .map(|r| (Features::of(&r.problem().unwrap()).unwrap(), r.difficulty.unwrap()))
+            .collect();
+        assert_eq!(samples.len(), 9);
+        assert!(BUILTIN_MODEL.accuracy(&samples) >= 0.85);
+        assert_eq!(BUILTIN_MODEL.estimate(&samples[0].0), Difficulty::Easy);
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::Display;
+use crate::problem::ProblemMode;
+
+// The errors that can happen when building problems from user input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownCard(u8),
+    UnknownLaw(u8),
+    InvalidMode(String),
+    InvalidDifficulty(String),
+    WrongNumberOfCards { mode: ProblemMode, count: usize },
+    InvalidCode(String),
+    InvalidProblem(String),
+    CardWithoutId(usize),
+    InvalidShareCode(String),
+    InvalidAnswer(String),
+    InvalidQuestion(String),
+    InvalidVerbosity(String),
+    InvalidStrategy(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownCard(id) => write!(f, "Card {} does not exist", id),
+            Error::UnknownLaw(id) => write!(f, "Law {} does not exist", id),
+            Error::InvalidMode(mode) => write!(f, "'{}' is not a game mode, it should be normal, extreme or nightmare", mode),
+            Error::InvalidDifficulty(d) => write!(f, "'{}' is not a difficulty, it should be easy, standard or hard", d),
+            Error::WrongNumberOfCards { mode, count } => {
+                let (min, max) = mode.num_cards();
+                write!(f, "A game in {} mode needs between {} and {} cards, but {} were given", mode, min, max, count)?;
+                if *mode == ProblemMode::Extreme {
+                    write!(f, " (extreme games need two cards per verifier)")?;
+                }
+                Ok(())
+            }
+            Error::InvalidCode(code) => write!(f, "'{}' is not a code, it should be three digits between 1 and 5", code),
+            Error::InvalidProblem(msg) => write!(f, "{}", msg),
+            Error::CardWithoutId(i) => write!(f, "Card {} of the problem is not part of a catalogue, so it has no id", i + 1),
+            Error::InvalidShareCode(reason) => write!(f, "Invalid share code: {}", reason),
+            Error::InvalidAnswer(answer) => write!(f, "'{}' is not an answer, it should look like 241:A=y", answer),
+            Error::InvalidQuestion(question) => write!(f, "'{}' is not a question, it should look like 241:A", question),
+            Error::InvalidVerbosity(v) => write!(f, "'{}' is not a verbosity, it should be quiet, normal or verbose", v),
+            Error::InvalidStrategy(s) => write!(f, "'{}' is not a strategy, it should be rounds or questions", s),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
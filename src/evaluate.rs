@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use crate::{constraint::Constraint, error::Error, problem::Problem, solve::{CommandLineVerifier, Solver}};
+
+// The number of rounds and questions needed by the solver for every world of a problem
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    pub rounds: Vec<usize>,
+    pub questions: Vec<usize>,
+    pub failures: usize, // Worlds for which the solver found a wrong code
+    pub num_worlds: usize,
+}
+
+fn mean(values: &[usize]) -> f32 {
+    values.iter().sum::<usize>() as f32 / values.len().max(1) as f32
+}
+
+// The number of times each value appears, by increasing value
+fn distribution(values: &[usize]) -> BTreeMap<usize, usize> {
+    values.iter().fold(BTreeMap::new(), |mut d, &v| {
+        *d.entry(v).or_default() += 1;
+        d
+    })
+}
+
+impl Evaluation {
+    // Runs the solver against every valid assignment of laws to the verifiers
+    pub fn of(problem: &Problem) -> Result<Evaluation, Error> {
+        Self::sample(problem, usize::MAX)
+    }
+
+    // Same as of, but only runs the solver on evenly spaced worlds when there are too many of them
+    pub fn sample(problem: &Problem, max_worlds: usize) -> Result<Evaluation, Error> {
+        let solver = Solver::<CommandLineVerifier>::from_problem(problem)?;
+        let worlds: Vec<_> = solver.worlds().collect();
+
+        // The deductions made before the first question are shared by all the worlds
+        let mut evaluation = Evaluation { num_worlds: worlds.len(), ..Default::default() };
+        for world in worlds.iter().step_by(worlds.len().div_ceil(max_worlds.max(1))) {
+            let code = Constraint::inter(world.iter().copied()).solution();
+            let mut solver = solver.clone().automatic(world.clone());
+            match solver.solve() {
+                Ok(sol) if Some(sol.code) == code => {
+                    evaluation.rounds.push(solver.num_rounds());
+                    evaluation.questions.push(solver.num_questions());
+                }
+                _ => evaluation.failures += 1,
+            }
+        }
+        Ok(evaluation)
+    }
+
+    // Adds the results of another evaluation, to get statistics over several problems
+    pub fn merge(&mut self, other: &Evaluation) {
+        self.rounds.extend(&other.rounds);
+        self.questions.extend(&other.questions);
+        self.failures += other.failures;
+        self.num_worlds += other.num_worlds;
+    }
+
+    pub fn mean_rounds(&self) -> f32 {
+        mean(&self.rounds)
+    }
+
+    pub fn mean_questions(&self) -> f32 {
+        mean(&self.questions)
+    }
+
+    pub fn worst_rounds(&self) -> usize {
+        self.rounds.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn worst_questions(&self) -> usize {
+        self.questions.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn rounds_distribution(&self) -> BTreeMap<usize, usize> {
+        distribution(&self.rounds)
+    }
+
+    pub fn questions_distribution(&self) -> BTreeMap<usize, usize> {
+        distribution(&self.questions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::problem::Problem;
+    use super::Evaluation;
+
+    #[test]
+    pub fn every_world_is_solved() {
+        let problem: Problem = "normal 4 9 11 14".parse().unwrap();
+        let evaluation = Evaluation::of(&problem).unwrap();
+        assert_eq!(evaluation.failures, 0);
+        assert_eq!(evaluation.questions.len(), evaluation.num_worlds);
+        assert_eq!(evaluation.questions_distribution().values().sum::<usize>(), evaluation.num_worlds);
+        assert!(evaluation.mean_questions() <= evaluation.worst_questions() as f32);
+
+        let sampled = Evaluation::sample(&"extreme 5 16 1 14 9 13 3 18".parse().unwrap(), 10).unwrap();
+        assert!(sampled.rounds.len() <= 10 && sampled.num_worlds > 10);
+    }
+}
@@ -0,0 +1,688 @@
+use std::{fmt::Display, iter::Peekable, str::FromStr};
+use itertools::Itertools;
+use crate::{code::{Code, Digit, Symbol}, constraint::Constraint};
+
+// A quantity that can be computed from a code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Count {
+    Digit(Digit), Even, Odd, Distinct, StepsUp, Steps
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Digit(Symbol),
+    Const(u8),
+    Count(Count),
+    Sum(Vec<Value>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmp {
+    Eq, Ne, Lt, Le, Gt, Ge
+}
+
+// The symbolic form of a law, as written on the criteria cards
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Bool(bool),
+    Compare(Value, Vec<(Cmp, Value)>), // Chained comparison, e.g. ▲<■<●
+    CompareEach(Value, Cmp, Vec<Symbol>), // Comparison with each of the symbols, e.g. ▲≤■●
+    Multiple(Value, u8), // Multiple of a number, e.g. ▲+■+●=3n
+    Even(Value),
+    Odd(Value),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+// The only multiple of 0 is 0 itself. The parser rejects 0n, but expressions can also be built directly.
+fn is_multiple(v: i32, n: u8) -> bool {
+    if n == 0 { v == 0 } else { v % n as i32 == 0 }
+}
+
+impl Count {
+    pub fn eval(&self, code: Code) -> i32 {
+        let even = |d: Digit| d.is_multiple_of(2);
+        (match self {
+            Count::Digit(v) => code.count(|d| d == *v),
+            Count::Even => code.count(even),
+            Count::Odd => code.count(|d| !even(d)),
+            Count::Distinct => code.num_distinct(),
+            Count::StepsUp => code.count_adj(|a, b| a + 1 == b),
+            Count::Steps => code.count_adj(|a, b| a + 1 == b).max(code.count_adj(|a, b| a == b + 1)),
+        }) as i32
+    }
+}
+
+impl Value {
+    pub fn eval(&self, code: Code) -> i32 {
+        match self {
+            Value::Digit(s) => code[*s] as i32,
+            Value::Const(v) => *v as i32,
+            Value::Count(c) => c.eval(code),
+            Value::Sum(vs) => vs.iter().map(|v| v.eval(code)).sum(),
+        }
+    }
+}
+
+impl Cmp {
+    pub fn eval(&self, a: i32, b: i32) -> bool {
+        match self {
+            Cmp::Eq => a == b,
+            Cmp::Ne => a != b,
+            Cmp::Lt => a < b,
+            Cmp::Le => a <= b,
+            Cmp::Gt => a > b,
+            Cmp::Ge => a >= b,
+        }
+    }
+}
+
+impl Expr {
+    pub fn accepts(&self, code: Code) -> bool {
+        match self {
+            Expr::Bool(b) => *b,
+            Expr::Compare(first, rest) => {
+                let mut lhs = first.eval(code);
+                rest.iter().all(|(op, v)| {
+                    let rhs = v.eval(code);
+                    let res = op.eval(lhs, rhs);
+                    lhs = rhs;
+                    res
+                })
+            }
+            Expr::CompareEach(v, op, symbols) => {
+                let lhs = v.eval(code);
+                symbols.iter().all(|s| op.eval(lhs, code[*s] as i32))
+            }
+            Expr::Multiple(v, n) => is_multiple(v.eval(code), *n),
+            Expr::Even(v) => v.eval(code) % 2 == 0,
+            Expr::Odd(v) => v.eval(code) % 2 != 0,
+            Expr::Not(e) => !e.accepts(code),
+            Expr::And(es) => es.iter().all(|e| e.accepts(code)),
+            Expr::Or(es) => es.iter().any(|e| e.accepts(code)),
+        }
+    }
+
+    // Computes the set of codes accepted by the expression
+    pub fn constraint(&self) -> Constraint {
+        Constraint::new(|c| self.accepts(c))
+    }
+}
+
+// The notations in which expressions can be printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    Symbols, // The notation of the cards, e.g. ▲+■≤6
+    Ascii, // Same as symbols, but can be typed on any keyboard, e.g. T+S<=6
+    Words, // Plain english, e.g. triangle + square is at most 6
+}
+
+fn render_symbol(s: Symbol, notation: Notation) -> String {
+    match (notation, s) {
+        (Notation::Symbols, s) => s.to_string(),
+        (Notation::Ascii, Symbol::Triangle) => "T".into(),
+        (Notation::Ascii, Symbol::Square) => "S".into(),
+        (Notation::Ascii, Symbol::Circle) => "C".into(),
+        (Notation::Words, Symbol::Triangle) => "triangle".into(),
+        (Notation::Words, Symbol::Square) => "square".into(),
+        (Notation::Words, Symbol::Circle) => "circle".into(),
+    }
+}
+
+impl Count {
+    pub fn render(&self, notation: Notation) -> String {
+        match (notation, self) {
+            (Notation::Words, Count::Digit(d)) => format!("the number of {}s", d),
+            (Notation::Words, Count::Even) => "the number of even digits".into(),
+            (Notation::Words, Count::Odd) => "the number of odd digits".into(),
+            (Notation::Words, Count::Distinct) => "the number of distinct digits".into(),
+            (Notation::Words, Count::StepsUp) => "the number of ascending steps".into(),
+            (Notation::Words, Count::Steps) => "the number of steps in the same direction".into(),
+            (_, Count::Digit(d)) => format!("#{}", d),
+            (_, Count::Even) => "#even".into(),
+            (_, Count::Odd) => "#odd".into(),
+            (_, Count::Distinct) => "#distinct".into(),
+            (_, Count::StepsUp) => "#steps-up".into(),
+            (_, Count::Steps) => "#steps".into(),
+        }
+    }
+}
+
+impl Value {
+    pub fn render(&self, notation: Notation) -> String {
+        match self {
+            Value::Digit(s) => render_symbol(*s, notation),
+            Value::Const(v) => v.to_string(),
+            Value::Count(c) => c.render(notation),
+            Value::Sum(vs) => vs.iter().map(|v| v.render(notation))
+                .join(if notation == Notation::Words { " + " } else { "+" }),
+        }
+    }
+
+    fn as_const(&self) -> Option<u8> {
+        if let Value::Const(v) = self { Some(*v) } else { None }
+    }
+
+    fn terms(&self, out: &mut Vec<Value>) {
+        match self {
+            Value::Sum(vs) => vs.iter().for_each(|v| v.terms(out)),
+            v => out.push(v.clone()),
+        }
+    }
+
+    // Flattens nested sums and adds up their constant terms, unless their total doesn't fit in a constant
+    pub fn simplify(&self) -> Value {
+        let mut terms = Vec::new();
+        self.terms(&mut terms);
+        if let Some(total) = terms.iter().filter_map(|v| v.as_const()).try_fold(0u8, |a, b| a.checked_add(b)) {
+            terms.retain(|v| v.as_const().is_none());
+            if total > 0 || terms.is_empty() {
+                terms.push(Value::Const(total));
+            }
+        }
+        if terms.len() == 1 { terms.pop().unwrap() } else { Value::Sum(terms) }
+    }
+}
+
+impl Cmp {
+    pub fn render(&self, notation: Notation) -> &'static str {
+        match (notation, self) {
+            (Notation::Symbols, Cmp::Ne) => "≠",
+            (Notation::Symbols, Cmp::Le) => "≤",
+            (Notation::Symbols, Cmp::Ge) => "≥",
+            (Notation::Ascii, Cmp::Ne) => "!=",
+            (Notation::Ascii, Cmp::Le) => "<=",
+            (Notation::Ascii, Cmp::Ge) => ">=",
+            (Notation::Words, Cmp::Eq) => " equals ",
+            (Notation::Words, Cmp::Ne) => " differs from ",
+            (Notation::Words, Cmp::Lt) => " is less than ",
+            (Notation::Words, Cmp::Le) => " is at most ",
+            (Notation::Words, Cmp::Gt) => " is greater than ",
+            (Notation::Words, Cmp::Ge) => " is at least ",
+            (_, Cmp::Eq) => "=",
+            (_, Cmp::Lt) => "<",
+            (_, Cmp::Gt) => ">",
+        }
+    }
+
+    // The comparison that holds exactly when this one doesn't
+    pub fn negate(&self) -> Cmp {
+        match self {
+            Cmp::Eq => Cmp::Ne,
+            Cmp::Ne => Cmp::Eq,
+            Cmp::Lt => Cmp::Ge,
+            Cmp::Le => Cmp::Gt,
+            Cmp::Gt => Cmp::Le,
+            Cmp::Ge => Cmp::Lt,
+        }
+    }
+}
+
+impl Expr {
+    pub fn render(&self, notation: Notation) -> String {
+        let words = notation == Notation::Words;
+        match self {
+            Expr::Bool(b) => b.to_string(),
+            Expr::Compare(first, rest) => {
+                let mut res = first.render(notation);
+                for (i, (op, v)) in rest.iter().enumerate() {
+                    if words && i > 0 {
+                        res.push_str(", which");
+                    }
+                    res.push_str(op.render(notation));
+                    res.push_str(&v.render(notation));
+                }
+                res
+            }
+            Expr::CompareEach(v, op, symbols) if words => {
+                let (last, init) = symbols.split_last().unwrap();
+                format!("{}{}{} and {}", v.render(notation), op.render(notation).trim_end(),
+                    init.iter().map(|s| format!(" {}", render_symbol(*s, notation))).join(","),
+                    render_symbol(*last, notation))
+            }
+            Expr::CompareEach(v, op, symbols) =>
+                format!("{}{}{}", v.render(notation), op.render(notation), symbols.iter().map(|s| render_symbol(*s, notation)).join("")),
+            Expr::Multiple(v, n) if words => format!("{} is a multiple of {}", v.render(notation), n),
+            Expr::Multiple(v, n) => format!("{}={}n", v.render(notation), n),
+            Expr::Even(v) if words => format!("{} is even", v.render(notation)),
+            Expr::Even(v) => format!("even({})", v.render(notation)),
+            Expr::Odd(v) if words => format!("{} is odd", v.render(notation)),
+            Expr::Odd(v) => format!("odd({})", v.render(notation)),
+            Expr::Not(e) if words => format!("not ({})", e.render(notation)),
+            Expr::Not(e) => format!("not({})", e.render(notation)),
+            Expr::And(es) => es.iter()
+                .map(|e| if matches!(e, Expr::Or(_)) { format!("({})", e.render(notation)) } else { e.render(notation) })
+                .join(if words { " and " } else { "&" }),
+            Expr::Or(es) => es.iter().map(|e| e.render(notation)).join(if words { " or " } else { "|" }),
+        }
+    }
+
+    // Rewrites the expression into an equivalent, simpler one
+    pub fn simplify(&self) -> Expr {
+        match self {
+            Expr::Bool(b) => Expr::Bool(*b),
+            Expr::Compare(first, rest) => {
+                let first = first.simplify();
+                let rest: Vec<_> = rest.iter().map(|(op, v)| (*op, v.simplify())).collect();
+                if first.as_const().is_some() && rest.iter().all(|(_, v)| v.as_const().is_some()) {
+                    // Comparisons between constants don't depend on the code
+                    Expr::Bool(Expr::Compare(first, rest).accepts(Code::new(1, 1, 1)))
+                } else if rest.len() == 1 && first == rest[0].1 {
+                    Expr::Bool(rest[0].0.eval(0, 0))
+                } else {
+                    Expr::Compare(first, rest)
+                }
+            }
+            Expr::CompareEach(v, op, symbols) => {
+                let symbols: Vec<_> = symbols.iter().copied().unique().collect();
+                if symbols.len() == 1 {
+                    Expr::Compare(v.clone(), vec![(*op, Value::Digit(symbols[0]))]).simplify()
+                } else {
+                    Expr::CompareEach(v.simplify(), *op, symbols)
+                }
+            }
+            Expr::Multiple(v, n) => match v.simplify() {
+                _ if *n == 1 => Expr::Bool(true),
+                Value::Const(c) => Expr::Bool(is_multiple(c as i32, *n)),
+                v => Expr::Multiple(v, *n),
+            }
+            Expr::Even(v) => match v.simplify() {
+                Value::Const(c) => Expr::Bool(c.is_multiple_of(2)),
+                v => Expr::Even(v),
+            }
+            Expr::Odd(v) => match v.simplify() {
+                Value::Const(c) => Expr::Bool(!c.is_multiple_of(2)),
+                v => Expr::Odd(v),
+            }
+            Expr::Not(e) => match e.simplify() {
+                Expr::Bool(b) => Expr::Bool(!b),
+                Expr::Not(e) => *e,
+                Expr::Compare(v, mut rest) if rest.len() == 1 => {
+                    let (op, w) = rest.pop().unwrap();
+                    Expr::Compare(v, vec![(op.negate(), w)])
+                }
+                Expr::Even(v) => Expr::Odd(v),
+                Expr::Odd(v) => Expr::Even(v),
+                e => Expr::Not(Box::new(e)),
+            }
+            Expr::And(es) => Self::simplify_connective(es, true),
+            Expr::Or(es) => Self::simplify_connective(es, false),
+        }
+    }
+
+    // Simplifies a conjunction (when is_and is true) or a disjunction
+    fn simplify_connective(es: &[Expr], is_and: bool) -> Expr {
+        let mut res = Vec::new();
+        for e in es.iter().map(|e| e.simplify()) {
+            let inner = match e {
+                Expr::Bool(b) if b == is_and => continue,
+                Expr::Bool(b) => return Expr::Bool(b),
+                Expr::And(inner) if is_and => inner,
+                Expr::Or(inner) if !is_and => inner,
+                e => vec![e],
+            };
+            for e in inner {
+                if !res.contains(&e) {
+                    res.push(e);
+                }
+            }
+        }
+        match res.len() {
+            0 => Expr::Bool(is_and),
+            1 => res.pop().unwrap(),
+            _ if is_and => Expr::And(res),
+            _ => Expr::Or(res),
+        }
+    }
+}
+
+impl Display for Count {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Notation::Symbols))
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Notation::Symbols))
+    }
+}
+
+impl Display for Cmp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Notation::Symbols))
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Notation::Symbols))
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Symbol(Symbol),
+    Number(u8),
+    Multiple(u8),
+    Cmp(Cmp),
+    Plus, Hash, LParen, RParen, Or, And,
+    Word(String),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Symbol(s) => write!(f, "{}", s),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Multiple(n) => write!(f, "{}n", n),
+            Token::Cmp(op) => write!(f, "{}", op),
+            Token::Plus => write!(f, "+"),
+            Token::Hash => write!(f, "#"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Or => write!(f, "|"),
+            Token::And => write!(f, "&"),
+            Token::Word(w) => write!(f, "{}", w),
+        }
+    }
+}
+
+// Splits the input into tokens, along with their position in the input
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    fn next_is(chars: &mut Peekable<impl Iterator<Item = (usize, char)>>, c: char) -> bool {
+        chars.next_if(|&(_, n)| n == c).is_some()
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+    while let Some((pos, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '▲' | 'T' => Token::Symbol(Symbol::Triangle),
+            '■' | 'S' => Token::Symbol(Symbol::Square),
+            '●' | 'C' => Token::Symbol(Symbol::Circle),
+            '0'..='9' => {
+                let mut n = c.to_digit(10).unwrap();
+                while let Some((_, d)) = chars.next_if(|(_, d)| d.is_ascii_digit()) {
+                    n = n.saturating_mul(10).saturating_add(d.to_digit(10).unwrap());
+                }
+                let n = u8::try_from(n).map_err(|_| ParseError { position: pos, message: format!("Number {} is too big", n) })?;
+                if next_is(&mut chars, 'n') { Token::Multiple(n) } else { Token::Number(n) }
+            }
+            '=' => Token::Cmp(Cmp::Eq),
+            '≠' => Token::Cmp(Cmp::Ne),
+            '≤' => Token::Cmp(Cmp::Le),
+            '≥' => Token::Cmp(Cmp::Ge),
+            '!' if next_is(&mut chars, '=') => Token::Cmp(Cmp::Ne),
+            '<' => Token::Cmp(if next_is(&mut chars, '=') { Cmp::Le } else { Cmp::Lt }),
+            '>' => Token::Cmp(if next_is(&mut chars, '=') { Cmp::Ge } else { Cmp::Gt }),
+            '+' => Token::Plus,
+            '#' => Token::Hash,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '|' => Token::Or,
+            '&' => Token::And,
+            'a'..='z' => {
+                let mut word = c.to_string();
+                while let Some((_, l)) = chars.next_if(|(_, l)| l.is_ascii_lowercase() || *l == '-') {
+                    word.push(l);
+                }
+                Token::Word(word)
+            }
+            _ => return Err(ParseError { position: pos, message: format!("Unexpected character '{}'", c) }),
+        };
+        tokens.push((pos, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.peek().cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        let position = self.tokens.get(self.pos).map_or(self.end, |(p, _)| *p);
+        Err(ParseError { position, message })
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T, ParseError> {
+        match self.peek() {
+            Some(t) => self.error(format!("Expected {}, found '{}'", expected, t)),
+            None => self.error(format!("Expected {}, found end of input", expected)),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ParseError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.unexpected(&format!("'{}'", token))
+        }
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut es = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            es.push(self.and()?);
+        }
+        Ok(if es.len() == 1 { es.pop().unwrap() } else { Expr::Or(es) })
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut es = vec![self.atom()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            es.push(self.atom()?);
+        }
+        Ok(if es.len() == 1 { es.pop().unwrap() } else { Expr::And(es) })
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let e = self.expr()?;
+            self.expect(Token::RParen)?;
+            return Ok(e);
+        }
+        for b in [true, false] {
+            if self.is_word(&b.to_string()) {
+                self.pos += 1;
+                return Ok(Expr::Bool(b));
+            }
+        }
+        for word in ["not", "even", "odd"] {
+            if self.is_word(word) {
+                self.pos += 1;
+                self.expect(Token::LParen)?;
+                let e = match word {
+                    "not" => Expr::Not(Box::new(self.expr()?)),
+                    "even" => Expr::Even(self.value()?),
+                    _ => Expr::Odd(self.value()?),
+                };
+                self.expect(Token::RParen)?;
+                return Ok(e);
+            }
+        }
+        self.comparison()
+    }
+
+    fn cmp(&mut self) -> Result<Cmp, ParseError> {
+        match self.peek() {
+            Some(Token::Cmp(op)) => { let op = *op; self.pos += 1; Ok(op) }
+            _ => self.unexpected("a comparison"),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let lhs = self.value()?;
+        let op = self.cmp()?;
+
+        // Multiple of a number
+        if let Some(&Token::Multiple(n)) = self.peek() {
+            if op != Cmp::Eq || n == 0 {
+                return self.error(format!("Invalid multiple comparison '{}{}n'", op, n));
+            }
+            self.pos += 1;
+            return Ok(Expr::Multiple(lhs, n));
+        }
+
+        // Comparison with several symbols
+        let mut symbols = Vec::new();
+        while let Some(&Token::Symbol(s)) = self.peek() {
+            self.pos += 1;
+            symbols.push(s);
+        }
+        if symbols.len() > 1 {
+            return Ok(Expr::CompareEach(lhs, op, symbols));
+        }
+        self.pos -= symbols.len();
+
+        // Chained comparison
+        let mut rest = vec![(op, self.value()?)];
+        while let Some(Token::Cmp(_)) = self.peek() {
+            let op = self.cmp()?;
+            rest.push((op, self.value()?));
+        }
+        Ok(Expr::Compare(lhs, rest))
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        let mut vs = vec![self.term()?];
+        while self.peek() == Some(&Token::Plus) {
+            self.pos += 1;
+            vs.push(self.term()?);
+        }
+        Ok(if vs.len() == 1 { vs.pop().unwrap() } else { Value::Sum(vs) })
+    }
+
+    fn term(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some(&Token::Symbol(s)) => { self.pos += 1; Ok(Value::Digit(s)) }
+            Some(&Token::Number(n)) => { self.pos += 1; Ok(Value::Const(n)) }
+            Some(Token::Hash) => {
+                self.pos += 1;
+                let count = match self.peek() {
+                    Some(&Token::Number(d)) if (1..=5).contains(&d) => Count::Digit(d),
+                    Some(Token::Word(w)) if w == "even" => Count::Even,
+                    Some(Token::Word(w)) if w == "odd" => Count::Odd,
+                    Some(Token::Word(w)) if w == "distinct" => Count::Distinct,
+                    Some(Token::Word(w)) if w == "steps-up" => Count::StepsUp,
+                    Some(Token::Word(w)) if w == "steps" => Count::Steps,
+                    _ => return self.unexpected("a digit or a count after '#'"),
+                };
+                self.pos += 1;
+                Ok(Value::Count(count))
+            }
+            _ => self.unexpected("a value"),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(s)?, pos: 0, end: s.chars().count() };
+        let expr = parser.expr()?;
+        if parser.next().is_some() {
+            parser.pos -= 1;
+            return parser.unexpected("end of input");
+        }
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cards::constraint_from_id, code::Code, constraint::Constraint};
+    use super::{Count, Expr, Notation, Value};
+
+    #[test]
+    pub fn catalogue_laws_round_trip() {
+        for id in 1..=145 {
+            let law = constraint_from_id(id).unwrap();
+            for notation in [Notation::Symbols, Notation::Ascii] {
+                let text = law.render(notation);
+                let expr: Expr = text.parse().unwrap_or_else(|e| panic!("Law {} ({}): {}", id, text, e));
+                assert_eq!(expr, law.expr, "Law {}", id);
+            }
+            assert!(law.expr.simplify().constraint() == law.constraint, "Law {} ({})", id, law);
+        }
+    }
+
+    #[test]
+    pub fn catalogue_laws_match_the_original_constraints() {
+        // The codes accepted by each law, as computed by the hand-written constraints that the expressions replaced
+        for line in include_str!("../data/law-codes.txt").lines() {
+            let (id, codes) = line.split_once(' ').unwrap();
+            let (id, codes) = (id.parse().unwrap(), u128::from_str_radix(codes, 16).unwrap());
+            let expected = Constraint::new(|c| codes & (1 << c.index()) != 0);
+            let law = constraint_from_id(id).unwrap();
+            assert!(law.constraint == expected, "Law {} ({})", id, law);
+            assert!(law.expr.simplify().constraint() == expected, "Law {} ({})", id, law);
+        }
+    }
+
+    #[test]
+    pub fn ascii_notation_is_accepted() {
+        assert_eq!("T+S >= 6".parse::<Expr>().unwrap(), "▲+■≥6".parse().unwrap());
+        assert_eq!("not(T<S<C | T>S>C)".parse::<Expr>().unwrap().to_string(), "not(▲<■<●|▲>■>●)");
+        assert!("▲+".parse::<Expr>().is_err());
+        assert!("▲<■●=3n".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    pub fn simplification() {
+        let simplify = |s: &str| s.parse::<Expr>().unwrap().simplify().to_string();
+        assert_eq!(simplify("not(not(▲<3))"), "▲<3");
+        assert_eq!(simplify("not(▲+■<6)"), "▲+■≥6");
+        assert_eq!(simplify("▲+2+1>■ & 2<3"), "▲+3>■");
+        assert_eq!(simplify("▲=■ | ▲=■ | (●=1 | ■=1)"), "▲=■|●=1|■=1");
+        assert_eq!(simplify("▲≤■■ & ●=3n & #even≠#even"), "false");
+        assert_eq!(simplify("▲+200+100>■"), "▲+200+100>■");
+        assert_eq!(simplify("▲+200+55>■"), "▲+255>■");
+    }
+
+    #[test]
+    pub fn multiples_of_zero() {
+        let zero = Expr::Multiple(Value::Count(Count::Digit(5)), 0);
+        assert!(zero.accepts(Code::new(1, 2, 3)) && !zero.accepts(Code::new(1, 2, 5)));
+        assert!(zero.constraint() == "#5=0".parse::<Expr>().unwrap().constraint());
+        assert_eq!(Expr::Multiple(Value::Const(0), 0).simplify(), Expr::Bool(true));
+    }
+}
@@ -0,0 +1,290 @@
+use std::{collections::HashSet, fmt::Display, fs, path::Path, str::FromStr};
+use itertools::Itertools;
+use json::JsonValue;
+use crate::{catalogue::Catalogue, code::Code, constraint::Constraint, error::Error, problem::{Problem, ProblemMode}};
+
+// The database of the games fetched from the official website
+pub const GAMES_PATH: &str = "data/games.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy, Standard, Hard
+}
+
+// A game of the official website, along with the answer of its AI. Generated games have neither
+// a difficulty nor an answer of the AI, since only the website gives them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub hash: String,
+    pub mode: ProblemMode,
+    pub difficulty: Option<Difficulty>,
+    pub cards: Vec<u8>,
+    pub laws: Vec<u8>, // The law of every verifier
+    pub solution: Code,
+    pub num_questions_ai: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum DatabaseError {
+    Io(std::io::Error),
+    Json(json::Error),
+    InvalidRecord { index: usize, reason: String },
+}
+
+// A filter on the games of the database, where unset fields match every game
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub mode: Option<ProblemMode>,
+    pub difficulty: Option<Difficulty>,
+    pub cards: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GameDatabase {
+    records: Vec<GameRecord>,
+}
+
+// Hashes are compared without spaces nor case, since they are often typed by hand
+fn normalize_hash(hash: &str) -> String {
+    hash.chars().filter(|c| !c.is_whitespace()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+fn same_cards(a: &[u8], b: &[u8]) -> bool {
+    a.iter().sorted().eq(b.iter().sorted())
+}
+
+impl FromStr for Difficulty {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "standard" => Ok(Difficulty::Standard),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(Error::InvalidDifficulty(s.into())),
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Standard => write!(f, "standard"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+impl GameRecord {
+    pub fn from_json(obj: &JsonValue) -> Result<GameRecord, String> {
+        let ids = |key: &str| obj[key].members()
+            .map(|v| v.as_u8().ok_or_else(|| format!("Invalid value {} in {}", v, key)))
+            .collect::<Result<Vec<_>, _>>();
+        let str_field = |key: &str| obj[key].as_str().ok_or_else(|| format!("Missing field {}", key));
+
+        let solution = ids("solution")?.into_iter().map(|d| d.to_string()).join("");
+        Ok(GameRecord {
+            hash: str_field("hash")?.into(),
+            mode: str_field("mode")?.parse().map_err(|e: Error| e.to_string())?,
+            difficulty: if obj["difficulty"].is_null() {
+                None
+            } else {
+                Some(str_field("difficulty")?.parse().map_err(|e: Error| e.to_string())?)
+            },
+            cards: ids("cards")?,
+            laws: ids("laws")?,
+            solution: solution.parse().map_err(|e: Error| e.to_string())?,
+            num_questions_ai: if obj["num-questions-ai"].is_null() {
+                None
+            } else {
+                Some(obj["num-questions-ai"].as_usize().ok_or("Invalid field num-questions-ai")?)
+            },
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json::object! {
+            "hash": self.hash.clone(),
+            "mode": self.mode.to_string(),
+            "difficulty": self.difficulty.map(|d| d.to_string()),
+            "cards": self.cards.clone(),
+            "laws": self.laws.clone(),
+            "solution": [self.solution[0u8], self.solution[1u8], self.solution[2u8]],
+            "num-questions-ai": self.num_questions_ai,
+        }
+    }
+
+    // The game comes from the official website, rather than from the generator
+    pub fn is_official(&self) -> bool {
+        self.difficulty.is_some() && self.num_questions_ai.is_some()
+    }
+
+    pub fn problem(&self) -> Result<Problem, Error> {
+        self.problem_in(Catalogue::builtin())
+    }
+
+    pub fn problem_in(&self, catalogue: &Catalogue) -> Result<Problem, Error> {
+        Problem::from_catalogue(catalogue, self.mode, self.cards.clone())
+    }
+
+    // The constraints of the verifiers, that is the secret of the game
+    pub fn constraints(&self, catalogue: &Catalogue) -> Result<Vec<Constraint>, Error> {
+        self.laws.iter().map(|&id| catalogue.law(id).map(|l| l.constraint)).collect()
+    }
+
+    // Checks that the laws of the game belong to the cards of the verifiers and define its solution
+    pub fn validate(&self, catalogue: &Catalogue) -> Result<(), String> {
+        let problem = self.problem_in(catalogue).map_err(|e| e.to_string())?;
+        let constraints = self.constraints(catalogue).map_err(|e| e.to_string())?;
+
+        // The intersection of the constraints should give the unique solution of the problem
+        if Constraint::inter(constraints.iter().copied()).solution() != Some(self.solution) {
+            return Err(format!("The laws don't define the solution {}", self.solution));
+        }
+
+        // Each verifier should have a single constraint associated with it
+        let possible_constraints: Vec<Vec<_>> = problem.verifier_cards().into_iter()
+            .map(|cards| cards.iter().flat_map(|c| c.constraints()).collect())
+            .collect();
+        if constraints.len() != possible_constraints.len() {
+            return Err(format!("The game has {} verifiers but {} laws", possible_constraints.len(), constraints.len()));
+        }
+        for ((constraint, possible), letter) in constraints.iter().zip(possible_constraints).zip("ABCDEF".chars()) {
+            if possible.into_iter().filter(|c| c == constraint).count() != 1 {
+                return Err(format!("The law of verifier {} is not on its cards", letter));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Query {
+    pub fn mode(mut self, mode: ProblemMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    // Matches the games that use exactly these cards, in any order
+    pub fn cards(mut self, cards: Vec<u8>) -> Self {
+        self.cards = Some(cards);
+        self
+    }
+
+    pub fn matches(&self, record: &GameRecord) -> bool {
+        self.mode.is_none_or(|m| m == record.mode)
+            && self.difficulty.is_none_or(|d| Some(d) == record.difficulty)
+            && self.cards.as_ref().is_none_or(|c| same_cards(c, &record.cards))
+    }
+}
+
+impl GameDatabase {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameDatabase, DatabaseError> {
+        Self::from_json(&fs::read_to_string(path).map_err(DatabaseError::Io)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), DatabaseError> {
+        fs::write(path, self.to_json()).map_err(DatabaseError::Io)
+    }
+
+    pub fn from_json(s: &str) -> Result<GameDatabase, DatabaseError> {
+        let root = json::parse(s).map_err(DatabaseError::Json)?;
+        let records = root.members().enumerate()
+            .map(|(index, obj)| GameRecord::from_json(obj).map_err(|reason| DatabaseError::InvalidRecord { index, reason }))
+            .collect::<Result<_, _>>()?;
+        Ok(GameDatabase { records })
+    }
+
+    // Writes the database with one game per line
+    pub fn to_json(&self) -> String {
+        format!("[\n  {}\n]\n", self.records.iter().map(|r| json::stringify(r.to_json())).join(",\n  "))
+    }
+
+    pub fn records(&self) -> &[GameRecord] {
+        &self.records
+    }
+
+    // Adds a game to the database, unless a game with the same hash is already present
+    pub fn append(&mut self, record: GameRecord) -> bool {
+        if self.by_hash(&record.hash).is_some() {
+            return false;
+        }
+        self.records.push(record);
+        true
+    }
+
+    // Removes the games whose hash appears earlier in the database, and returns how many were removed
+    pub fn dedup(&mut self) -> usize {
+        let len = self.records.len();
+        let mut seen = HashSet::new();
+        self.records.retain(|r| seen.insert(normalize_hash(&r.hash)));
+        len - self.records.len()
+    }
+
+    // Returns the games that are not consistent with the catalogue, along with the reason why
+    pub fn validate(&self, catalogue: &Catalogue) -> Vec<(&GameRecord, String)> {
+        self.records.iter().filter_map(|r| r.validate(catalogue).err().map(|e| (r, e))).collect()
+    }
+
+    pub fn by_hash(&self, hash: &str) -> Option<&GameRecord> {
+        let hash = normalize_hash(hash);
+        self.records.iter().find(|r| normalize_hash(&r.hash) == hash)
+    }
+
+    pub fn query<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = &'a GameRecord> {
+        self.records.iter().filter(|r| query.matches(r))
+    }
+}
+
+impl Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseError::Io(e) => write!(f, "Could not read the game database: {}", e),
+            DatabaseError::Json(e) => write!(f, "The game database is not valid JSON: {}", e),
+            DatabaseError::InvalidRecord { index, reason } => write!(f, "Game {} of the database is invalid: {}", index + 1, reason),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::problem::ProblemMode;
+    use super::{Difficulty, GameDatabase, GameRecord, Query, GAMES_PATH};
+
+    #[test]
+    pub fn database_queries() {
+        let mut db = GameDatabase::load(GAMES_PATH).unwrap();
+        assert_eq!(GameDatabase::from_json(&db.to_json()).unwrap().records(), db.records());
+
+        let game = db.by_hash("a43ubk").unwrap().clone();
+        assert_eq!(game.cards, vec![4, 7, 13, 15]);
+        assert!(!db.append(game.clone()));
+
+        let query = Query::default().mode(ProblemMode::Nightmare).difficulty(Difficulty::Hard);
+        assert!(db.query(&query).all(|r| r.mode == ProblemMode::Nightmare && r.difficulty == Some(Difficulty::Hard)));
+        assert_eq!(db.query(&Query::default().cards(vec![15, 13, 7, 4])).count(), 1);
+
+        let len = db.records().len();
+        db.records.push(game);
+        assert_eq!(db.dedup(), 1);
+        assert_eq!(db.records().len(), len);
+    }
+
+    // Malformed ids are reported instead of being dropped or replaced, which would silently change the game
+    #[test]
+    pub fn malformed_records_are_rejected() {
+        let record = |cards: &str, laws: &str| json::parse(&format!(
+            r#"{{"hash":"X","mode":"normal","difficulty":"easy","cards":{},"laws":{},"solution":[2,4,1],"num-questions-ai":3}}"#, cards, laws)).unwrap();
+        assert!(GameRecord::from_json(&record("[4,9,11,14]", "[7,18,22,34]")).is_ok());
+        assert!(GameRecord::from_json(&record("[4,\"9\",11,14]", "[7,18,22,34]")).is_err());
+        assert!(GameRecord::from_json(&record("[4,9,11,300]", "[7,18,22,34]")).is_err());
+        assert!(GameRecord::from_json(&record("[4,9,11,14]", "[7,18,null,34]")).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{analyze::analyze, catalogue::Catalogue, constraint::Constraint, error::Error, games::GameRecord, problem::{Problem, ProblemMode}, solve::{AutomaticVerifier, Solver}};
+
+// Number of law assignments tried on a set of cards before picking other cards
+const ATTEMPTS_PER_CARDS: usize = 100;
+// Number of sets of cards tried before giving up, for catalogues that can't make valid games
+const MAX_CARD_SETS: usize = 10_000;
+
+// SplitMix64, which is enough to pick cards and makes games reproducible from their seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// Generates random games whose laws define a unique solution, like the ones of the official website
+pub struct Generator<'a> {
+    catalogue: &'a Catalogue,
+    mode: Option<ProblemMode>,
+    num_verifiers: Option<usize>,
+    skip_trivial: bool,
+    seed: u64,
+    rng: Rng,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(catalogue: &'a Catalogue) -> Generator<'a> {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+        Generator { catalogue, mode: None, num_verifiers: None, skip_trivial: false, seed, rng: Rng(seed) }
+    }
+
+    // Makes the sequence of generated games reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = Rng(seed);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Only generates games in this mode, instead of a random one
+    pub fn mode(mut self, mode: ProblemMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    // Only generates games with this number of verifiers, between 4 and 6
+    pub fn num_verifiers(mut self, num_verifiers: usize) -> Self {
+        self.num_verifiers = Some(num_verifiers);
+        self
+    }
+
+    // Only generates games that need at least one question to be solved
+    pub fn skip_trivial(mut self) -> Self {
+        self.skip_trivial = true;
+        self
+    }
+
+    // Picks distinct cards, sorted like on the website (by pair of cards in extreme mode)
+    fn pick_cards(&mut self, mode: ProblemMode, num_verifiers: usize) -> Vec<u8> {
+        let mut ids: Vec<u8> = self.catalogue.cards().filter_map(|c| c.id).collect();
+        self.rng.shuffle(&mut ids);
+        let num_cards = if mode == ProblemMode::Extreme { 2 * num_verifiers } else { num_verifiers };
+        ids.truncate(num_cards);
+        if mode == ProblemMode::Extreme {
+            ids.chunks_mut(2).for_each(|pair| pair.sort());
+            let mut pairs: Vec<_> = ids.chunks(2).map(|p| p.to_vec()).collect();
+            pairs.sort();
+            pairs.concat()
+        } else {
+            ids.sort();
+            ids
+        }
+    }
+
+    // Picks a law with an id for every verifier, along with the index of the card it comes from
+    fn pick_laws(&mut self, problem: &Problem) -> Option<Vec<(usize, u8)>> {
+        let num_verifiers = problem.num_verifiers();
+        let mut cards: Vec<usize> = match problem.mode {
+            ProblemMode::Extreme => (0..num_verifiers).map(|v| 2 * v + self.rng.below(2)).collect(),
+            _ => (0..num_verifiers).collect(),
+        };
+        if problem.mode == ProblemMode::Nightmare {
+            self.rng.shuffle(&mut cards);
+        }
+        cards.into_iter().map(|i| {
+            let laws: Vec<u8> = problem.cards[i].laws.iter().filter_map(|l| l.id).collect();
+            if laws.is_empty() { None } else { Some((i, *self.rng.pick(&laws))) }
+        }).collect()
+    }
+
+    // Generates a game, and checks that the solver finds its solution. The game has no difficulty nor
+    // answer of the official AI, so that it can't be mistaken for a game of the website.
+    pub fn generate(&mut self) -> Result<GameRecord, Error> {
+        let modes = [ProblemMode::Normal, ProblemMode::Extreme, ProblemMode::Nightmare];
+        let mode = self.mode.unwrap_or_else(|| *self.rng.pick(&modes));
+        let num_verifiers = self.num_verifiers.unwrap_or_else(|| 4 + self.rng.below(3));
+        let num_cards = if mode == ProblemMode::Extreme { 2 * num_verifiers } else { num_verifiers };
+        let (min, max) = mode.num_cards();
+        if num_cards < min || num_cards > max {
+            return Err(Error::WrongNumberOfCards { mode, count: num_cards });
+        }
+        if self.catalogue.cards().filter(|c| c.id.is_some()).count() < num_cards {
+            return Err(Error::InvalidProblem(format!("The catalogue doesn't have the {} cards needed by the game", num_cards)));
+        }
+
+        for _ in 0..MAX_CARD_SETS {
+            let cards = self.pick_cards(mode, num_verifiers);
+            let problem = Problem::from_catalogue(self.catalogue, mode, cards.clone())?;
+            // Whether the game can be solved without questions only depends on its cards
+            if self.skip_trivial && analyze(&problem).is_ok_and(|a| a.is_determined()) {
+                continue;
+            }
+            for _ in 0..ATTEMPTS_PER_CARDS {
+                let Some(picked) = self.pick_laws(&problem) else { break };
+
+                // The solver groups the constraints by card in nightmare mode, and by verifier otherwise
+                let constraints: Vec<Constraint> = picked.iter().enumerate().map(|(v, &(card, id))| {
+                    let group = if mode == ProblemMode::Nightmare { card } else { v };
+                    self.catalogue.law(id).map(|l| l.constraint.with_group(group as u8))
+                }).collect::<Result<_, _>>()?;
+                if !Solver::<AutomaticVerifier>::valid_constraints(&constraints) {
+                    continue;
+                }
+
+                let laws: Vec<u8> = picked.iter().map(|&(_, id)| id).collect();
+                let solution = Constraint::inter(constraints.iter().copied()).solution().expect("The laws define a unique solution");
+                let record = GameRecord {
+                    hash: problem.to_share_code(Some(&laws))?, // The share code identifies the game, like the official hashes
+                    mode,
+                    difficulty: None,
+                    cards: cards.clone(),
+                    laws,
+                    solution,
+                    num_questions_ai: None,
+                };
+                // Laws that appear on several cards make the verifiers ambiguous
+                if record.validate(self.catalogue).is_err() {
+                    continue;
+                }
+                let mut solver = Solver::<AutomaticVerifier>::new(&problem).automatic_laws(self.catalogue, &record.laws)?;
+                if solver.solve().is_ok_and(|sol| sol.code == solution) {
+                    return Ok(record);
+                }
+            }
+        }
+        Err(Error::InvalidProblem("The catalogue can't make a game with a unique solution".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{analyze::analyze, catalogue::Catalogue, problem::ProblemMode};
+    use super::Generator;
+
+    #[test]
+    pub fn generated_games_are_valid_and_reproducible() {
+        let catalogue = Catalogue::builtin();
+        let mut generator = Generator::new(catalogue).with_seed(42).mode(ProblemMode::Normal);
+        let games: Vec<_> = (0..5).map(|_| generator.generate().unwrap()).collect();
+        for game in games.iter() {
+            assert!(game.validate(catalogue).is_ok(), "{:?}", game);
+            assert!(!game.is_official());
+        }
+
+        let mut generator = Generator::new(catalogue).with_seed(42).mode(ProblemMode::Normal);
+        assert_eq!(generator.generate().unwrap(), games[0]);
+        assert!(Generator::new(catalogue).mode(ProblemMode::Extreme).num_verifiers(7).generate().is_err());
+
+        let mut generator = Generator::new(catalogue).with_seed(42).mode(ProblemMode::Normal).skip_trivial();
+        let game = generator.generate().unwrap();
+        assert!(!analyze(&game.problem().unwrap()).unwrap().is_determined());
+    }
+}
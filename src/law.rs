@@ -0,0 +1,80 @@
+use std::{fmt::Display, path::{Path, PathBuf}, str::FromStr};
+use crate::{code::Code, constraint::Constraint, expr::{Expr, Notation, ParseError}};
+
+// The directory containing the pictures of the verification cards, named after the law ids
+pub const LAW_IMAGES_DIR: &str = "data/laws";
+
+// A law of a criteria card, kept both in symbolic form and as the set of codes it accepts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Law {
+    pub id: Option<u8>, // The number of the law in the official game, if any
+    pub name: String,
+    pub expr: Expr,
+    pub constraint: Constraint,
+}
+
+impl Law {
+    pub fn new(expr: Expr) -> Law {
+        let constraint = expr.constraint();
+        Law { id: None, name: expr.to_string(), expr, constraint }
+    }
+
+    pub fn with_id(mut self, id: u8) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    // The picture of the verification card that corresponds to the law, if there is one
+    pub fn image(&self) -> Option<PathBuf> {
+        let path = Path::new(LAW_IMAGES_DIR).join(format!("{:03}.jpg", self.id?));
+        if path.exists() { Some(path) } else { None }
+    }
+
+    // Describes how to find the law in the physical game
+    pub fn label(&self) -> String {
+        match (self.id, self.image()) {
+            (Some(id), Some(image)) => format!("law {} ({}), verification card {}", id, self, image.display()),
+            (Some(id), None) => format!("law {} ({})", id, self),
+            (None, _) => format!("law {} (no verification card)", self),
+        }
+    }
+
+    pub fn accepts(&self, code: Code) -> bool {
+        self.constraint.accepts(code)
+    }
+
+    pub fn render(&self, notation: Notation) -> String {
+        self.expr.render(notation)
+    }
+
+    // Simplifies the expression of the law, keeping its name unless it was the expression itself
+    pub fn simplify(&self) -> Law {
+        let expr = self.expr.simplify();
+        let name = if self.name == self.expr.to_string() { expr.to_string() } else { self.name.clone() };
+        Law { id: self.id, name, expr, constraint: self.constraint }
+    }
+
+    // Two laws are equivalent if they accept the same codes, even if they are written differently
+    pub fn is_equivalent_to(&self, other: &Law) -> bool {
+        self.constraint.with_group(0) == other.constraint.with_group(0)
+    }
+}
+
+impl FromStr for Law {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Law::new)
+    }
+}
+
+impl Display for Law {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -1,5 +1,22 @@
+pub mod analyze;
+pub mod bench;
+pub mod coach;
 pub mod code;
 pub mod constraint;
+pub mod difficulty;
+pub mod error;
+pub mod evaluate;
+pub mod expr;
+pub mod games;
+pub mod generate;
+pub mod law;
+pub mod playlog;
+pub mod lint;
 pub mod problem;
+pub mod protocol;
+pub mod share;
 pub mod solve;
+pub mod strategy;
+pub mod whatif;
 pub mod cards;
+pub mod catalogue;
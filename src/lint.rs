@@ -0,0 +1,139 @@
+use std::{collections::HashSet, fmt::Display};
+use itertools::Itertools;
+use crate::{catalogue::Catalogue, law::Law, problem::Card};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning, Error
+}
+
+// A mistake found in the definition of the cards of a catalogue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    DuplicateLaw { card: u8, law: String },
+    EquivalentLaws { card: u8, first: String, second: String },
+    NeverTrue { law: String },
+    AlwaysTrue { law: String },
+    IdenticalCards(u8, u8),
+    UnusedLaw(u8),
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::UnusedLaw(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+fn describe(law: &Law) -> String {
+    match law.id {
+        Some(id) => format!("law {} ({})", id, law),
+        None => format!("law {}", law),
+    }
+}
+
+fn same_laws(a: &Card, b: &Card) -> bool {
+    a.laws.len() == b.laws.len()
+        && a.laws.iter().all(|l| b.laws.iter().any(|m| l.is_equivalent_to(m)))
+        && b.laws.iter().all(|l| a.laws.iter().any(|m| l.is_equivalent_to(m)))
+}
+
+// Checks the laws and cards of the catalogue, and returns the issues sorted by decreasing severity
+pub fn lint(catalogue: &Catalogue) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let cards = catalogue.cards().map(|c| (c.id.unwrap_or_default(), c)).collect_vec();
+
+    // Laws that can't be used to discriminate codes
+    let inline_laws = cards.iter().flat_map(|(_, c)| c.laws.iter().filter(|l| l.id.is_none()));
+    for law in catalogue.laws().chain(inline_laws) {
+        match law.constraint.num_solutions() {
+            0 => issues.push(Issue::NeverTrue { law: describe(law) }),
+            125 => issues.push(Issue::AlwaysTrue { law: describe(law) }),
+            _ => {}
+        }
+    }
+
+    // Laws that appear several times on the same card
+    for (id, card) in cards.iter() {
+        for (a, b) in card.laws.iter().tuple_combinations() {
+            if a.id.is_some() && a.id == b.id {
+                issues.push(Issue::DuplicateLaw { card: *id, law: describe(a) });
+            } else if a.is_equivalent_to(b) {
+                issues.push(Issue::EquivalentLaws { card: *id, first: describe(a), second: describe(b) });
+            }
+        }
+    }
+
+    // Cards that are interchangeable
+    for ((a, c), (b, d)) in cards.iter().tuple_combinations() {
+        if same_laws(c, d) {
+            issues.push(Issue::IdenticalCards(*a, *b));
+        }
+    }
+
+    // Laws that no card can use
+    let used: HashSet<_> = cards.iter().flat_map(|(_, c)| c.laws.iter().filter_map(|l| l.id)).collect();
+    issues.extend(catalogue.laws().filter_map(|l| l.id).filter(|id| !used.contains(id)).map(Issue::UnusedLaw));
+
+    issues.sort_by_key(|i| std::cmp::Reverse(i.severity()));
+    issues
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::DuplicateLaw { card, law } => write!(f, "Card {} contains {} several times", card, law),
+            Issue::EquivalentLaws { card, first, second } =>
+                write!(f, "Card {} contains {} and {} which accept the same codes", card, first, second),
+            Issue::NeverTrue { law } => write!(f, "The {} accepts no code", law),
+            Issue::AlwaysTrue { law } => write!(f, "The {} accepts every code", law),
+            Issue::IdenticalCards(a, b) => write!(f, "Cards {} and {} have the same laws", a, b),
+            Issue::UnusedLaw(id) => write!(f, "Law {} is not used by any card", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{catalogue::Catalogue, law::Law, problem::Card};
+    use super::{lint, Issue, Severity};
+
+    #[test]
+    pub fn builtin_catalogue_is_valid() {
+        let errors: Vec<_> = lint(Catalogue::builtin()).into_iter().filter(|i| i.severity() == Severity::Error).collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    pub fn mistakes_are_reported() {
+        let mut catalogue = Catalogue::default();
+        let law = |s: &str| s.parse::<Law>().unwrap();
+        catalogue.insert_law(1, law("▲=1"));
+        catalogue.insert_law(2, law("▲<2"));
+        catalogue.insert_law(3, law("▲>5"));
+        catalogue.insert_law(4, law("■=2"));
+        let laws = |ids: &[u8]| Card::new(ids.iter().map(|&id| catalogue.law(id).unwrap().clone()).collect());
+        let (c1, c2, c3) = (laws(&[1, 1]), laws(&[1, 2, 3]), laws(&[2, 3, 1]));
+        catalogue.insert_card(1, c1);
+        catalogue.insert_card(2, c2);
+        catalogue.insert_card(3, c3);
+
+        let issues = lint(&catalogue);
+        assert!(issues.contains(&Issue::NeverTrue { law: "law 3 (▲>5)".into() }));
+        assert!(issues.contains(&Issue::DuplicateLaw { card: 1, law: "law 1 (▲=1)".into() }));
+        assert!(issues.contains(&Issue::EquivalentLaws { card: 2, first: "law 1 (▲=1)".into(), second: "law 2 (▲<2)".into() }));
+        assert!(issues.contains(&Issue::IdenticalCards(2, 3)));
+        assert_eq!(issues.last(), Some(&Issue::UnusedLaw(4)));
+    }
+}
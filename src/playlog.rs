@@ -0,0 +1,170 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+use crate::{bench::{GameResult, Outcome}, catalogue::Catalogue, error::Error, evaluate::Evaluation, games::GameDatabase, problem::{Problem, ProblemMode}};
+
+// The log of the games that we played ourselves
+pub const PLAY_LOG_PATH: &str = "data/games.txt";
+
+// The number of rounds and questions needed to solve a game, written "rounds/questions"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub rounds: usize,
+    pub questions: usize,
+}
+
+// A line of the log: the id of the game, its problem, and the scores of the player, of the
+// official AI and of this solver when the game was played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayRecord {
+    pub id: String,
+    pub mode: ProblemMode,
+    pub cards: Vec<u8>,
+    pub me: Score,
+    pub ai: Score,
+    pub my_ai: Score,
+}
+
+#[derive(Debug)]
+pub enum LogError {
+    Io(std::io::Error),
+    InvalidLine { line: usize, reason: String }, // The line number in the file, starting at 1 with the header
+}
+
+// The result of the solver on a logged game. The laws of our games are not recorded,
+// so the solver is run against every possible law assignment unless the game is in the database.
+pub enum Replay {
+    Official(Score),
+    AllWorlds(Evaluation),
+}
+
+impl FromStr for Score {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rounds, questions) = s.trim().split_once('/').ok_or_else(|| format!("'{}' is not a score like 3/8", s.trim()))?;
+        let parse = |n: &str| n.trim().parse().map_err(|_| format!("'{}' is not a score like 3/8", s.trim()));
+        Ok(Score { rounds: parse(rounds)?, questions: parse(questions)? })
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.rounds, self.questions)
+    }
+}
+
+impl PlayRecord {
+    // Solves the game with the official laws if they are known, and against every possible laws otherwise
+    pub fn replay(&self, db: &GameDatabase, catalogue: &Catalogue) -> Result<Replay, Error> {
+        if let Some((rounds, questions)) = db.by_hash(&self.id).and_then(|r| GameResult::run(r, catalogue)?.solved) {
+            return Ok(Replay::Official(Score { rounds, questions }));
+        }
+        Evaluation::of(&Problem::from_catalogue(catalogue, self.mode, self.cards.clone())?).map(Replay::AllWorlds)
+    }
+}
+
+impl Replay {
+    pub fn mean_rounds(&self) -> f32 {
+        match self {
+            Replay::Official(s) => s.rounds as f32,
+            Replay::AllWorlds(e) => e.mean_rounds(),
+        }
+    }
+
+    pub fn mean_questions(&self) -> f32 {
+        match self {
+            Replay::Official(s) => s.questions as f32,
+            Replay::AllWorlds(e) => e.mean_questions(),
+        }
+    }
+
+    pub fn worst_questions(&self) -> usize {
+        match self {
+            Replay::Official(s) => s.questions,
+            Replay::AllWorlds(e) => e.worst_questions(),
+        }
+    }
+
+    // The fractions of the worlds in which the solver needs fewer and more questions than a player
+    pub fn shares_against(&self, score: Score) -> (f32, f32) {
+        let questions = match self {
+            Replay::Official(s) => vec![s.questions],
+            Replay::AllWorlds(e) => e.questions.clone(),
+        };
+        let share = |f: fn(&usize, &usize) -> bool| {
+            questions.iter().filter(|q| f(q, &score.questions)).count() as f32 / questions.len().max(1) as f32
+        };
+        (share(usize::lt), share(usize::gt))
+    }
+
+    // Compares the questions of the solver with the ones of a player. When the laws are unknown, the solver
+    // wins or loses if it does better or worse in most of the worlds, since the player only played one of them.
+    pub fn outcome_against(&self, score: Score) -> Outcome {
+        match self.shares_against(score) {
+            (better, _) if better > 0.5 => Outcome::Win,
+            (_, worse) if worse > 0.5 => Outcome::Loss,
+            _ => Outcome::Tie,
+        }
+    }
+}
+
+impl Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogError::Io(e) => write!(f, "Could not read the play log: {}", e),
+            LogError::InvalidLine { line, reason } => write!(f, "Line {} of the play log is invalid: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
+
+// Reads the log, which starts with a header line
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<PlayRecord>, LogError> {
+    parse(&fs::read_to_string(path).map_err(LogError::Io)?)
+}
+
+pub fn parse(s: &str) -> Result<Vec<PlayRecord>, LogError> {
+    s.lines().enumerate().skip(1).filter(|(_, l)| !l.trim().is_empty()).map(|(index, line)| {
+        let invalid = |reason: String| LogError::InvalidLine { line: index + 1, reason };
+        let fields: Vec<_> = line.split(',').map(|f| f.trim()).collect();
+        let [id, problem, me, ai, my_ai] = fields[..] else {
+            return Err(invalid(format!("Expected 5 fields but found {}", fields.len())));
+        };
+        let problem: Problem = problem.parse().map_err(|e: Error| invalid(e.to_string()))?;
+        Ok(PlayRecord {
+            id: id.into(),
+            mode: problem.mode,
+            cards: problem.card_ids().map_err(|e| invalid(e.to_string()))?,
+            me: me.parse().map_err(invalid)?,
+            ai: ai.parse().map_err(invalid)?,
+            my_ai: my_ai.parse().map_err(invalid)?,
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bench::Outcome, catalogue::Catalogue, games::GameDatabase};
+    use super::{load, parse, Replay, Score, PLAY_LOG_PATH};
+
+    #[test]
+    pub fn play_log() {
+        let log = load(PLAY_LOG_PATH).unwrap();
+        assert_eq!(log[0].id, "C472O7");
+        assert_eq!(log[0].cards, vec![16, 31, 46, 48]);
+        assert_eq!(log[0].me, Score { rounds: 3, questions: 8 });
+        assert_eq!(parse("id, problem, me, ai, my-ai\n\nX, normal 1 2 3 4, 3/8, 3/8").unwrap_err().to_string(),
+            "Line 3 of the play log is invalid: Expected 5 fields but found 4");
+        assert!(parse("id, problem, me, ai, my-ai\nX, normal 1 2 3 4, 3/8, 3, 2/2").is_err());
+
+        let replay = log[0].replay(&GameDatabase::default(), Catalogue::builtin()).unwrap();
+        assert!(matches!(replay, Replay::AllWorlds(_)));
+        assert!(replay.mean_questions() <= replay.worst_questions() as f32);
+        assert_eq!(Replay::Official(Score { rounds: 2, questions: 5 }).outcome_against(log[0].me), Outcome::Win);
+
+        // The solver needs at most 3 questions in every world, against 8 for the player
+        assert_eq!(replay.shares_against(log[0].me), (1.0, 0.0));
+        assert_eq!(replay.outcome_against(log[0].me), Outcome::Win);
+        assert_eq!(replay.outcome_against(Score { rounds: 1, questions: 1 }), Outcome::Loss);
+    }
+}
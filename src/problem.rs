@@ -1,33 +1,62 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use itertools::Itertools;
+use json::JsonValue;
 
-use crate::{cards::card_from_id, constraint::Constraint};
+use crate::{catalogue::Catalogue, constraint::Constraint, difficulty::{Features, BUILTIN_MODEL}, error::Error, games::Difficulty, law::Law, share};
 
-#[derive(Clone)]
-pub struct Card { pub constraints: Vec<(String, Constraint)> }
+#[derive(Clone, PartialEq)]
+pub struct Card { pub id: Option<u8>, pub laws: Vec<Law> }
 
 impl Card {
-    pub fn new(constraints: Vec<(String, Constraint)>) -> Card {
-        Card { constraints }
+    pub fn new(laws: Vec<Law>) -> Card {
+        Card { id: None, laws }
+    }
+
+    pub fn with_id(mut self, id: u8) -> Self {
+        self.id = Some(id);
+        self
     }
 
     pub fn constraints(&self) -> Vec<Constraint> {
-        self.constraints.iter().map(|kv| kv.1).collect()
+        self.laws.iter().map(|l| l.constraint).collect()
     }
 }
 
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", self.constraints.iter().map(|kv| &kv.0).join(", "))
+        write!(f, "[{}]", self.laws.iter().join(", "))
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ProblemMode {
     Normal, Extreme, Nightmare
 }
 
+impl ProblemMode {
+    // The minimum and maximum number of cards of a game in this mode
+    pub fn num_cards(&self) -> (usize, usize) {
+        match self {
+            ProblemMode::Normal | ProblemMode::Nightmare => (4, 6),
+            ProblemMode::Extreme => (8, 12),
+        }
+    }
+}
+
+impl FromStr for ProblemMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(ProblemMode::Normal),
+            "extreme" => Ok(ProblemMode::Extreme),
+            "nightmare" => Ok(ProblemMode::Nightmare),
+            _ => Err(Error::InvalidMode(s.into())),
+        }
+    }
+}
+
 impl Display for ProblemMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -38,6 +67,9 @@ impl Display for ProblemMode {
     }
 }
 
+// The number of cards is stored on four bits in share codes
+const MAX_SHARED_CARDS: usize = 15;
+
 pub struct Problem { pub cards: Vec<Card>, pub mode: ProblemMode }
 
 impl Problem {
@@ -45,8 +77,146 @@ impl Problem {
         Problem { cards, mode }
     }
 
-    pub fn from_card_ids(mode: ProblemMode, ids: Vec<u8>) -> Problem {
-        Problem { cards: ids.into_iter().map(card_from_id).collect(), mode }
+    pub fn from_card_ids(mode: ProblemMode, ids: Vec<u8>) -> Result<Problem, Error> {
+        Self::from_catalogue(Catalogue::builtin(), mode, ids)
+    }
+
+    pub fn from_catalogue(catalogue: &Catalogue, mode: ProblemMode, ids: Vec<u8>) -> Result<Problem, Error> {
+        let (min, max) = mode.num_cards();
+        let count = ids.len();
+        if count < min || count > max || (mode == ProblemMode::Extreme && !count.is_multiple_of(2)) {
+            return Err(Error::WrongNumberOfCards { mode, count });
+        }
+        let cards = ids.into_iter().map(|id| catalogue.card(id).cloned()).collect::<Result<_, _>>()?;
+        Ok(Problem { cards, mode })
+    }
+
+    pub fn num_verifiers(&self) -> usize {
+        if self.mode == ProblemMode::Extreme { self.cards.len() / 2 } else { self.cards.len() }
+    }
+
+    // The ids of the cards, which are needed to serialise the problem
+    // The cards that the law of each verifier can come from
+    pub fn verifier_cards(&self) -> Vec<Vec<&Card>> {
+        match self.mode {
+            ProblemMode::Normal => self.cards.iter().map(|c| vec![c]).collect(),
+            ProblemMode::Extreme => self.cards.chunks(2).map(|c| c.iter().collect()).collect(),
+            ProblemMode::Nightmare => std::iter::repeat_n(self.cards.iter().collect(), self.cards.len()).collect(),
+        }
+    }
+
+    pub fn card_ids(&self) -> Result<Vec<u8>, Error> {
+        self.cards.iter().enumerate().map(|(i, c)| c.id.ok_or(Error::CardWithoutId(i))).collect()
+    }
+
+    // Estimates the difficulty that the official website would give to the problem
+    pub fn estimate_difficulty(&self) -> Result<Difficulty, Error> {
+        Ok(BUILTIN_MODEL.estimate(&Features::of(self)?))
+    }
+
+    // Reads a problem from the mode and cards of an entry of the game database
+    pub fn from_json(catalogue: &Catalogue, obj: &JsonValue) -> Result<Problem, Error> {
+        let mode = obj["mode"].as_str().ok_or_else(|| Error::InvalidProblem(format!("Missing mode in {}", obj)))?.parse()?;
+        let ids = obj["cards"].members()
+            .map(|id| id.as_u8().ok_or_else(|| Error::InvalidProblem(format!("Invalid card id {}", id))))
+            .collect::<Result<_, _>>()?;
+        Self::from_catalogue(catalogue, mode, ids)
+    }
+
+    pub fn to_json(&self) -> Result<JsonValue, Error> {
+        Ok(json::object! { "mode": self.mode.to_string(), "cards": self.card_ids()? })
+    }
+
+    // Reads a problem written on one line as the mode followed by the card ids, e.g. "normal 16 31 46 48"
+    pub fn from_text(catalogue: &Catalogue, s: &str) -> Result<Problem, Error> {
+        let mut words = s.split_whitespace();
+        let mode = words.next().ok_or_else(|| Error::InvalidProblem("The problem is empty".into()))?.parse()?;
+        let ids = words
+            .map(|id| id.parse().map_err(|_| Error::InvalidProblem(format!("'{}' is not a card id", id))))
+            .collect::<Result<_, _>>()?;
+        Self::from_catalogue(catalogue, mode, ids)
+    }
+
+    pub fn to_text(&self) -> Result<String, Error> {
+        Ok(format!("{} {}", self.mode, self.card_ids()?.iter().join(" ")))
+    }
+
+    // Encodes the problem, and optionally the law of every verifier, as a short code like "0G81-YBHG-ZX2G"
+    pub fn to_share_code(&self, laws: Option<&[u8]>) -> Result<String, Error> {
+        let ids = self.card_ids()?;
+        let mode = match self.mode {
+            ProblemMode::Normal => 0,
+            ProblemMode::Extreme => 1,
+            ProblemMode::Nightmare => 2,
+        };
+        if laws.is_some_and(|l| l.len() != self.num_verifiers()) {
+            return Err(Error::InvalidShareCode(format!("The problem has {} verifiers, so it needs as many laws", self.num_verifiers())));
+        }
+
+        // The first byte holds the mode, whether the laws are present and the number of cards, on four bits
+        if ids.len() > MAX_SHARED_CARDS {
+            return Err(Error::InvalidShareCode(format!("Share codes hold at most {} cards, but the problem has {}", MAX_SHARED_CARDS, ids.len())));
+        }
+        let mut bytes = vec![(mode << 6) | ((laws.is_some() as u8) << 5) | ids.len() as u8];
+        bytes.extend(ids);
+        bytes.extend(laws.unwrap_or_default());
+        Ok(share::encode(&bytes))
+    }
+
+    // Decodes a share code, and returns the problem along with the laws of the verifiers if they were shared
+    pub fn from_share_code(catalogue: &Catalogue, code: &str) -> Result<(Problem, Option<Vec<u8>>), Error> {
+        let bytes = share::decode(code).map_err(Error::InvalidShareCode)?;
+        let (&header, rest) = bytes.split_first().ok_or_else(|| Error::InvalidShareCode("The share code is empty".into()))?;
+        let mode = match header >> 6 {
+            0 => ProblemMode::Normal,
+            1 => ProblemMode::Extreme,
+            2 => ProblemMode::Nightmare,
+            _ => return Err(Error::InvalidShareCode("Unknown game mode".into())),
+        };
+        if header & 16 != 0 {
+            return Err(Error::InvalidShareCode("Unknown header bit".into()));
+        }
+        let num_cards = header as usize & MAX_SHARED_CARDS;
+        if num_cards > rest.len() {
+            return Err(Error::InvalidShareCode("Some cards are missing".into()));
+        }
+        let (ids, laws) = rest.split_at(num_cards);
+        let problem = Self::from_catalogue(catalogue, mode, ids.to_vec())?;
+
+        let laws = if header & 32 != 0 {
+            if laws.len() != problem.num_verifiers() {
+                return Err(Error::InvalidShareCode(format!("Expected {} laws but found {}", problem.num_verifiers(), laws.len())));
+            }
+            for ((&id, cards), letter) in laws.iter().zip(problem.verifier_cards()).zip("ABCDEF".chars()) {
+                let law = catalogue.law(id)?;
+                if !cards.iter().any(|c| c.constraints().contains(&law.constraint)) {
+                    return Err(Error::InvalidShareCode(format!("Law {} is not on the cards of verifier {}", id, letter)));
+                }
+            }
+            Some(laws.to_vec())
+        } else if !laws.is_empty() {
+            return Err(Error::InvalidShareCode("Unexpected data after the cards".into()));
+        } else {
+            None
+        };
+        Ok((problem, laws))
+    }
+
+    // Reads a problem typed by a user, as a mode followed by card ids when there are several words, or as a share code
+    pub fn from_text_or_share_code(catalogue: &Catalogue, s: &str) -> Result<(Problem, Option<Vec<u8>>), Error> {
+        if s.split_whitespace().count() > 1 {
+            Self::from_text(catalogue, s).map(|p| (p, None))
+        } else {
+            Self::from_share_code(catalogue, s.trim())
+        }
+    }
+}
+
+impl FromStr for Problem {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_text(Catalogue::builtin(), s)
     }
 }
 
@@ -69,3 +239,49 @@ impl Display for Problem {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{catalogue::Catalogue, error::Error, share};
+    use super::{Problem, ProblemMode};
+
+    #[test]
+    pub fn problem_serialisation() {
+        let problem: Problem = "extreme  16 12 21 15 11 5 19 18".parse().unwrap();
+        assert!(problem.mode == ProblemMode::Extreme);
+        assert_eq!(problem.to_text().unwrap(), "extreme 16 12 21 15 11 5 19 18");
+
+        let json = problem.to_json().unwrap();
+        assert_eq!(json.dump(), r#"{"mode":"extreme","cards":[16,12,21,15,11,5,19,18]}"#);
+        let parsed = Problem::from_json(Catalogue::builtin(), &json).unwrap();
+        assert!(parsed.mode == problem.mode && parsed.cards == problem.cards);
+
+        assert_eq!("hard 1 2 3 4".parse::<Problem>().err(), Some(Error::InvalidMode("hard".into())));
+        assert_eq!("normal 1 2 x 4".parse::<Problem>().err(), Some(Error::InvalidProblem("'x' is not a card id".into())));
+        assert_eq!("normal 1 2 3 99".parse::<Problem>().err(), Some(Error::UnknownCard(99)));
+    }
+
+    #[test]
+    pub fn share_codes() {
+        let problem: Problem = "nightmare 16 31 46 48".parse().unwrap();
+        let code = problem.to_share_code(None).unwrap();
+        let (parsed, laws) = Problem::from_share_code(Catalogue::builtin(), &code).unwrap();
+        assert!(parsed.mode == problem.mode && parsed.cards == problem.cards && laws.is_none());
+
+        let code = problem.to_share_code(Some(&[131, 16, 46, 139])).unwrap();
+        let (parsed, laws) = Problem::from_share_code(Catalogue::builtin(), &code.to_lowercase()).unwrap();
+        assert!(parsed.cards == problem.cards && laws == Some(vec![131, 16, 46, 139]));
+        assert!(problem.to_share_code(Some(&[46])).is_err());
+        assert!(Problem::from_share_code(Catalogue::builtin(), &code[1..]).is_err());
+        assert!(Problem::from_text_or_share_code(Catalogue::builtin(), &code).unwrap().1.is_some());
+        assert!(Problem::from_text_or_share_code(Catalogue::builtin(), "nightmare 16 31 46 48").unwrap().0.cards == problem.cards);
+
+        let foreign_law = problem.to_share_code(Some(&[1, 16, 46, 139])).unwrap();
+        assert_eq!(Problem::from_share_code(Catalogue::builtin(), &foreign_law).err(),
+            Some(Error::InvalidShareCode("Law 1 is not on the cards of verifier A".into())));
+        assert!(Problem::from_share_code(Catalogue::builtin(), &share::encode(&[0b10010100, 16, 31, 46, 48])).is_err());
+
+        let too_many = Problem::from_cards(ProblemMode::Nightmare, vec![problem.cards[0].clone(); 16]);
+        assert!(too_many.to_share_code(None).is_err());
+    }
+}
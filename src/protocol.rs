@@ -0,0 +1,133 @@
+use itertools::Itertools;
+use crate::{catalogue::Catalogue, error::Error, problem::Problem, solve::{Answer, CommandLineVerifier, Solver, SolverError}};
+
+// A session of the line-based protocol, which lets other programs drive the solver through stdin and stdout.
+// Every command gets a single line of response, which starts with ok, error, question, solution or state:
+//   load PROBLEM       Loads a problem given as a mode followed by card ids, or as a share code
+//   next               Gives the next question to ask, like "question 241:A", or "solution 241" once the code is known
+//   answer 241:A=y     Records the answer of a verifier to a code
+//   state              Gives the number of rounds, questions and possible codes, and the constraints left per verifier
+//   undo               Cancels the last answer
+//   quit               Ends the session
+// The answers are given by the other program, so the verifiers are never asked on the command line.
+pub struct Session {
+    catalogue: Catalogue,
+    solver: Option<Solver<CommandLineVerifier>>,
+    history: Vec<Solver<CommandLineVerifier>>, // The state of the solver before each answer
+    finished: bool,
+}
+
+fn letter(verifier: usize) -> char {
+    "ABCDEF".chars().nth(verifier).unwrap()
+}
+
+impl Session {
+    pub fn new(catalogue: Catalogue) -> Session {
+        Session { catalogue, solver: None, history: Vec::new(), finished: false }
+    }
+
+    // The session is finished once quit was received
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // Runs a command, and returns the line to respond with
+    pub fn handle(&mut self, line: &str) -> String {
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').map_or((line, ""), |(c, a)| (c, a.trim()));
+        let response = match command {
+            "load" => self.load(argument),
+            "next" => self.next(),
+            "answer" => self.answer(argument),
+            "state" => self.state(),
+            "undo" => self.undo(),
+            "quit" => {
+                self.finished = true;
+                Ok("ok".into())
+            }
+            "" => Err("empty command".into()),
+            _ => Err(format!("unknown command {}", command)),
+        };
+        response.unwrap_or_else(|e| format!("error {}", e))
+    }
+
+    fn solver(&self) -> Result<&Solver<CommandLineVerifier>, String> {
+        self.solver.as_ref().ok_or_else(|| "no problem loaded".into())
+    }
+
+    fn load(&mut self, text: &str) -> Result<String, String> {
+        let (problem, _) = Problem::from_text_or_share_code(&self.catalogue, text).map_err(|e| e.to_string())?;
+        self.solver = Some(Solver::<CommandLineVerifier>::from_problem(&problem).map_err(|e| e.to_string())?);
+        self.history.clear();
+        Ok(format!("ok verifiers={}", problem.num_verifiers()))
+    }
+
+    fn next(&self) -> Result<String, String> {
+        let solver = self.solver()?;
+        if let Some(code) = solver.has_solution().map_err(|_| "the answers lead to several solutions")? {
+            return Ok(format!("solution {}", code));
+        }
+        match solver.next_question() {
+            Some((code, verifier)) => Ok(format!("question {}:{}", code, letter(verifier))),
+            None => Err("no question can tell the remaining laws apart".into()),
+        }
+    }
+
+    fn answer(&mut self, text: &str) -> Result<String, String> {
+        let answer: Answer = text.parse().map_err(|e: Error| e.to_string())?;
+        let mut next = self.solver()?.clone();
+        let before = next.num_constraints();
+        next.record_answers(&[answer]).map_err(|e| match e {
+            SolverError::UnknownVerifier(v) => format!("verifier {} is not part of the game", letter(v)),
+            SolverError::RoundIsFull => "round is full".into(),
+            SolverError::VerifierAlreadyAsked(_) => "verifier already asked".into(),
+            _ => "the answer contradicts the previous ones".into(),
+        })?;
+        let eliminated = before - next.num_constraints();
+        self.history.push(self.solver.replace(next).unwrap());
+        Ok(format!("ok eliminated={}", eliminated))
+    }
+
+    fn state(&self) -> Result<String, String> {
+        let solver = self.solver()?;
+        let constraints = solver.possible_assignments().iter().map(|laws| laws.len()).join(",");
+        Ok(format!("state rounds={} questions={} codes={} constraints={}", solver.num_rounds(), solver.num_questions(),
+            solver.possible_codes().iter().join(","), constraints))
+    }
+
+    fn undo(&mut self) -> Result<String, String> {
+        let previous = self.history.pop().ok_or("nothing to undo")?;
+        self.solver = Some(previous);
+        Ok("ok".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalogue::Catalogue;
+    use super::Session;
+
+    #[test]
+    pub fn protocol_session() {
+        let mut session = Session::new(Catalogue::builtin().clone());
+        assert_eq!(session.handle("next"), "error no problem loaded");
+        assert_eq!(session.handle("load normal 4 9 11 14"), "ok verifiers=4");
+        assert_eq!(session.handle("state"), "state rounds=0 questions=0 codes=221,241 constraints=2,1,2,1");
+        assert_eq!(session.handle("next"), "question 445:C");
+        assert_eq!(session.handle("answer 445:C=n"), "ok eliminated=2");
+        assert_eq!(session.handle("next"), "solution 241");
+        assert_eq!(session.handle("undo"), "ok");
+        assert_eq!(session.handle("answer 445:C=y"), "ok eliminated=2");
+        assert_eq!(session.handle("next"), "solution 221");
+        assert_eq!(session.handle("answer 333:B=y"), "error the answer contradicts the previous ones");
+        assert_eq!(session.handle("answer 333:F=y"), "error verifier F is not part of the game");
+        assert_eq!(session.handle("answer 445:C=y"), "error verifier already asked");
+        assert_eq!(session.handle("answer 445:A=n"), "ok eliminated=0");
+        assert_eq!(session.handle("answer 445:B=y"), "ok eliminated=0");
+        assert_eq!(session.handle("answer 445:D=n"), "error round is full");
+        assert_eq!(session.handle("state"), "state rounds=1 questions=3 codes=221 constraints=1,1,1,1");
+        assert!(session.handle("jump").starts_with("error"));
+        assert_eq!(session.handle("quit"), "ok");
+        assert!(session.is_finished());
+    }
+}
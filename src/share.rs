@@ -0,0 +1,88 @@
+use itertools::Itertools;
+
+// Codes are written in Crockford's base 32, which avoids the letters that look like digits
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+// Number of characters between the dashes of a share code
+const GROUP_LEN: usize = 4;
+
+// CRC-16/CCITT, which detects any typo changing up to three characters in a row
+pub fn checksum(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0xFFFF, |crc, &b| {
+        (0..8).fold(crc ^ ((b as u16) << 8), |crc, _| if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 })
+    })
+}
+
+// Encodes the bytes followed by their checksum, in groups of characters separated by dashes
+pub fn encode(bytes: &[u8]) -> String {
+    let mut data = bytes.to_vec();
+    data.extend(checksum(bytes).to_be_bytes());
+
+    let mut chars = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+    for b in data {
+        acc = (acc << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            chars.push(ALPHABET[((acc >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        chars.push(ALPHABET[((acc << (5 - bits)) & 31) as usize] as char);
+    }
+    chars.chunks(GROUP_LEN).map(|c| c.iter().collect::<String>()).join("-")
+}
+
+// Decodes a share code into the bytes it contains, after checking its checksum
+pub fn decode(code: &str) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let v = ALPHABET.iter().position(|&a| a as char == c).ok_or_else(|| format!("'{}' can't appear in a share code", c))?;
+        acc = (acc << 5) | v as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((acc >> bits) as u8);
+        }
+    }
+
+    // The last character is padded with zeros, which must be checked since they're not covered by the checksum
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err("The share code contains a typo".into());
+    }
+    if data.len() < 2 {
+        return Err("The share code is too short".into());
+    }
+    let sum = data.split_off(data.len() - 2);
+    if checksum(&data).to_be_bytes() != sum[..] {
+        return Err("The share code contains a typo".into());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    pub fn typos_are_detected() {
+        let bytes = [0x84, 16, 31, 46, 48];
+        let code = encode(&bytes);
+        assert_eq!(decode(&code).unwrap(), bytes);
+        assert_eq!(decode(&code.to_lowercase().replace('-', " ")).unwrap(), bytes);
+
+        for i in (0..code.len()).filter(|&i| &code[i..i + 1] != "-") {
+            for c in "0123456789ABCDEFGHJKMNPQRSTVWXYZ".chars().filter(|&c| c != code.chars().nth(i).unwrap()) {
+                let typo: String = code.chars().enumerate().map(|(j, d)| if i == j { c } else { d }).collect();
+                assert!(decode(&typo).is_err(), "{}", typo);
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use std::{collections::HashMap, iter::repeat};
+use std::{collections::HashMap, fmt::Display, iter::repeat_n, str::FromStr};
 use itertools::Itertools;
 use num::Rational32;
-use crate::{code::{Code, Symbol}, constraint::Constraint, problem::{Problem, ProblemMode}};
+use crate::{catalogue::Catalogue, code::{Code, Symbol}, constraint::Constraint, error::Error, law::Law, problem::{Card, Problem, ProblemMode}};
 
 // This trait is used to inject user input into the solver to run it in testing mode
 pub trait Verifier {
@@ -9,9 +9,11 @@ pub trait Verifier {
 }
 
 // This verifier asks the user about what a card answers for a given code
+#[derive(Clone)]
 pub struct CommandLineVerifier;
 
 // This verifier automatically answers what the card would answer given the constraint that they obey
+#[derive(Clone)]
 pub struct AutomaticVerifier(Vec<Constraint>);
 
 impl Verifier for CommandLineVerifier {
@@ -29,21 +31,174 @@ impl Verifier for AutomaticVerifier {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub enum SolverVerbosity {
     None, Normal, Verbose
 }
 
+// How the solver spends its questions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    // Asks each code to up to three verifiers, to find the code in as few rounds as possible
+    #[default]
+    Rounds,
+    // Asks a single verifier per round, so that every question takes all the previous answers into account
+    Questions,
+}
+
+impl Strategy {
+    pub fn questions_per_round(self) -> usize {
+        match self {
+            Strategy::Rounds => 3,
+            Strategy::Questions => 1,
+        }
+    }
+}
+
+impl FromStr for SolverVerbosity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quiet" => Ok(SolverVerbosity::None),
+            "normal" => Ok(SolverVerbosity::Normal),
+            "verbose" => Ok(SolverVerbosity::Verbose),
+            _ => Err(Error::InvalidVerbosity(s.into())),
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rounds" => Ok(Strategy::Rounds),
+            "questions" => Ok(Strategy::Questions),
+            _ => Err(Error::InvalidStrategy(s.into())),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::Rounds => write!(f, "rounds"),
+            Strategy::Questions => write!(f, "questions"),
+        }
+    }
+}
+
 pub enum SolverError {
     Impossible(Vec<usize>),
     MultipleSolutions(Vec<Code>),
+    UnknownVerifier(usize), // An answer was given for a verifier that is not part of the game
+    RoundIsFull, // An answer was given to the code of a round that already asked as many verifiers as allowed
+    VerifierAlreadyAsked(usize), // An answer was given twice by the same verifier in a round
+}
+
+// The card and law that a verifier turned out to use
+#[derive(Clone)]
+pub struct Assignment {
+    pub verifier: usize,
+    pub card: usize, // The index of the card in the problem
+    pub card_id: Option<u8>,
+    pub law: Law,
+}
+
+impl Assignment {
+    // The law, along with its card when the verifier has several cards to choose from
+    pub fn describe(&self, mode: ProblemMode) -> String {
+        let card = self.card_id.map_or(format!("#{}", self.card + 1), |id| id.to_string());
+        match (mode, self.law.id) {
+            (ProblemMode::Normal, Some(id)) => format!("{} (law {})", self.law, id),
+            (ProblemMode::Normal, None) => self.law.to_string(),
+            (_, Some(id)) => format!("{} (card {}, law {})", self.law, card, id),
+            (_, None) => format!("{} (card {})", self.law, card),
+        }
+    }
+}
+
+// Parses a code asked to a verifier, written like "241:A"
+pub fn parse_question(s: &str) -> Result<(Code, usize), Error> {
+    let invalid = || Error::InvalidQuestion(s.into());
+    let (code, verifier) = s.split_once(':').ok_or_else(invalid)?;
+    let verifier = match verifier.to_ascii_uppercase().as_str() {
+        v @ ("A" | "B" | "C" | "D" | "E" | "F") => (v.as_bytes()[0] - b'A') as usize,
+        _ => return Err(invalid()),
+    };
+    Ok((code.parse().map_err(|_| invalid())?, verifier))
+}
+
+// A question scored by the solver, with the expected eliminations of each verifier and the order in which to ask them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedQuestion {
+    pub code: Code,
+    pub score: f32,
+    pub eliminations: Vec<f32>,
+    pub verifiers: Vec<usize>,
+}
+
+fn to_f32(r: Rational32) -> f32 {
+    *r.numer() as f32 / *r.denom() as f32
 }
 
+// The answer of a verifier to a code, written like "241:A=y"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Answer {
+    pub code: Code,
+    pub verifier: usize,
+    pub accepted: bool,
+}
+
+impl FromStr for Answer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidAnswer(s.into());
+        let (question, answer) = s.split_once('=').ok_or_else(invalid)?;
+        let (code, verifier) = parse_question(question).map_err(|_| invalid())?;
+        let accepted = match answer.to_ascii_lowercase().as_str() {
+            "y" | "yes" => true,
+            "n" | "no" => false,
+            _ => return Err(invalid()),
+        };
+        Ok(Answer { code, verifier, accepted })
+    }
+}
+
+impl Answer {
+    // The error to report when the solver can't record the answer
+    pub fn rejected(&self, e: SolverError) -> Error {
+        match e {
+            SolverError::UnknownVerifier(_) => Error::InvalidProblem(format!("The answer {} is given by a verifier that is not part of the game", self)),
+            SolverError::RoundIsFull => Error::InvalidProblem(format!("The answer {} is given in a round that is already full", self)),
+            SolverError::VerifierAlreadyAsked(_) => Error::InvalidProblem(format!("The answer {} is given by a verifier that was already asked in the round", self)),
+            _ => Error::InvalidProblem(format!("The answer {} contradicts the previous ones", self)),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letter = "ABCDEF".chars().nth(self.verifier).unwrap_or('?');
+        write!(f, "{}:{}={}", self.code, letter, if self.accepted { "y" } else { "n" })
+    }
+}
+
+pub struct Solution {
+    pub code: Code,
+    pub assignment: Vec<Assignment>,
+}
+
+#[derive(Clone)]
 pub struct Solver<V: Verifier> {
+    cards: Vec<Vec<(usize, Card)>>, // The cards of the problem with their index, grouped like the constraints
     verifiers: Vec<Vec<Constraint>>, // The set of constraints for every verifier
     questions: Vec<Code>, // The questions that were asked
     answers: Vec<HashMap<usize, bool>>, // The answers that were given
     verbosity: SolverVerbosity,
+    strategy: Strategy,
     verifier: V,
 }
 
@@ -67,35 +222,94 @@ impl<V: Verifier> Solver<V> {
                         .map(|c| c.constraints())
                         .collect()
                     ).into_iter().flatten().collect();
-                repeat(constraints).take(problem.cards.len()).collect()
+                repeat_n(constraints, problem.cards.len()).collect()
             }
         };
         Solver {
+            cards: match problem.mode {
+                ProblemMode::Extreme => problem.cards.iter().cloned().enumerate().chunks(2).into_iter().map(|c| c.collect()).collect(),
+                _ => problem.cards.iter().cloned().enumerate().map(|c| vec![c]).collect(),
+            },
             verifiers,
             questions: Vec::new(),
             answers: Vec::new(),
             verbosity: SolverVerbosity::Normal,
+            strategy: Strategy::default(),
             verifier: CommandLineVerifier,
         }
     }
 
+    // A quiet solver for a problem, which already made the deductions that need no question
+    pub fn from_problem(problem: &Problem) -> Result<Solver<CommandLineVerifier>, Error> {
+        let mut solver = Self::new(problem).verbosity(SolverVerbosity::None);
+        if solver.eliminate().is_err() || solver.worlds().next().is_none() {
+            return Err(Error::InvalidProblem("The cards can't define a unique solution".into()));
+        }
+        Ok(solver)
+    }
+
     // Change the verbosity of the solver
     pub fn verbosity(mut self, verbosity: SolverVerbosity) -> Self {
         self.verbosity = verbosity;
         self
     }
 
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    // The number of verifiers that the strategy asks a code to, at most
+    pub fn questions_per_round(&self) -> usize {
+        self.strategy.questions_per_round()
+    }
+
     // Puts the solver into automatic mode: outputs nothing and automatically answers questions. Useful for testing.
     pub fn automatic(self, verifiers: Vec<Constraint>) -> Solver<AutomaticVerifier> {
         Solver {
+            cards: self.cards,
             verifiers: self.verifiers,
             questions: self.questions,
             answers: self.answers,
             verbosity: SolverVerbosity::None,
+            strategy: self.strategy,
             verifier: AutomaticVerifier(verifiers),
         }
     }
 
+    // Same as automatic, but the verifiers are given by the ids of their laws in the catalogue
+    pub fn automatic_laws(self, catalogue: &Catalogue, ids: &[u8]) -> Result<Solver<AutomaticVerifier>, Error> {
+        let verifiers = ids.iter().map(|&id| catalogue.law(id).map(|l| l.constraint)).collect::<Result<_, _>>()?;
+        Ok(self.automatic(verifiers))
+    }
+
+    // Returns the card and law that a constraint of a verifier comes from
+    pub fn assignment_of(&self, verifier: usize, constraint: Constraint) -> Option<Assignment> {
+        let c = constraint.with_group(0);
+        self.cards.get(constraint.group() as usize)?.iter().find_map(|(i, card)| {
+            let law = card.laws.iter().find(|l| l.constraint == c)?;
+            Some(Assignment { verifier, card: *i, card_id: card.id, law: law.clone() })
+        })
+    }
+
+    // Returns the card and law of every verifier, once they are all known
+    pub fn assignment(&self) -> Option<Vec<Assignment>> {
+        self.verifiers.iter().enumerate()
+            .map(|(v, cs)| Self::known_constraint(cs).and_then(|c| self.assignment_of(v, c)))
+            .collect()
+    }
+
+    // Returns the cards and laws that each verifier can still use
+    pub fn possible_assignments(&self) -> Vec<Vec<Assignment>> {
+        self.verifiers.iter().enumerate()
+            .map(|(v, cs)| cs.iter().filter_map(|&c| self.assignment_of(v, c)).collect())
+            .collect()
+    }
+
+    pub fn num_verifiers(&self) -> usize {
+        self.verifiers.len()
+    }
+
     pub fn num_rounds(&self) -> usize {
         self.questions.len()
     }
@@ -104,25 +318,52 @@ impl<V: Verifier> Solver<V> {
         self.answers.iter().map(|a| a.len()).sum()
     }
 
+    // The number of constraints that are still possible, summed over the verifiers
+    pub fn num_constraints(&self) -> usize {
+        self.verifiers.iter().map(|cs| cs.len()).sum()
+    }
+
+    // The combinations of one constraint per verifier that are still possible, each of them defining a solution
+    pub fn worlds(&self) -> impl Iterator<Item = Vec<Constraint>> + '_ {
+        self.verifiers.iter().map(|cs| cs.iter().copied()).multi_cartesian_product()
+            .filter(|constraints| Self::valid_constraints(constraints))
+    }
+
+    // The constraint of a verifier once it is known. Equivalent laws can be left on different cards,
+    // since no question can tell them apart, in which case the first card is picked.
+    fn known_constraint(constraints: &[Constraint]) -> Option<Constraint> {
+        let first = constraints.first()?;
+        constraints.iter().all(|c| c.with_group(0) == first.with_group(0)).then_some(*first)
+    }
+
     fn assign_groups(mut constraints: Vec<Vec<Constraint>>) -> Vec<Vec<Constraint>> {
         constraints.iter_mut().enumerate()
-            .for_each(|(i, cs)| cs.into_iter().for_each(|c| *c = c.with_group(i as u8)));
+            .for_each(|(i, cs)| cs.iter_mut().for_each(|c| *c = c.with_group(i as u8)));
         constraints
     }
 
     // Prints the state of the solver
     pub fn print_state(&self) {
+        for line in self.state_lines() {
+            println!("{}", line);
+        }
+    }
+
+    // The grid of the questions that were asked and of their answers, line by line
+    pub fn state_lines(&self) -> Vec<String> {
         if self.questions.is_empty() {
-            println!("No questions asked yet.");
-        } else {
-            println!("{} │ {}", Symbol::all_symbols().join(""), "ABCDEF".chars().take(self.verifiers.len()).join(" "));
-            println!("────┼{}", "─".repeat(2 * self.verifiers.len()));
-            for (q, a) in self.questions.iter().zip(self.answers.iter()) {
-                let mut answers = (0..self.verifiers.len())
-                    .map(|i| a.get(&i).map_or("☐", |&r| if r { "☑" } else { "☒" }));
-                println!("{} │ {}", format!("{}", q), answers.join(" "))
-            }
+            return vec!["No questions asked yet.".into()];
+        }
+        let mut lines = vec![
+            format!("{} │ {}", Symbol::all_symbols().join(""), "ABCDEF".chars().take(self.verifiers.len()).join(" ")),
+            format!("────┼{}", "─".repeat(2 * self.verifiers.len())),
+        ];
+        for (q, a) in self.questions.iter().zip(self.answers.iter()) {
+            let mut answers = (0..self.verifiers.len())
+                .map(|i| a.get(&i).map_or("☐", |&r| if r { "☑" } else { "☒" }));
+            lines.push(format!("{} │ {}", q, answers.join(" ")));
         }
+        lines
     }
 
     fn err_if_invalid(&self) -> Result<(), SolverError> {
@@ -132,9 +373,8 @@ impl<V: Verifier> Solver<V> {
     }
 
     // Checks if a solution was found or not, or returns an error if several solutions are possible
-    fn has_solution(&self) -> Result<Option<Code>, SolverError> {
-        let known_constraints = self.verifiers.iter()
-            .map(|cs| if cs.len() == 1 { Some(cs[0]) } else { None }).collect::<Vec<_>>();
+    pub fn has_solution(&self) -> Result<Option<Code>, SolverError> {
+        let known_constraints = self.verifiers.iter().map(|cs| Self::known_constraint(cs)).collect::<Vec<_>>();
 
         if known_constraints.iter().all(|c| c.is_some()) {
             let cons = Constraint::inter(known_constraints.iter().flatten().copied());
@@ -146,24 +386,46 @@ impl<V: Verifier> Solver<V> {
         }
     }
 
-    // Finds the best question to ask with the elimination heuristic
-    fn best_question(&self) -> Code {
+    // Scores every code with the elimination heuristic, from the best to the worst question
+    fn scores(&self) -> Vec<(Rational32, Code)> {
         let mut questions = Vec::with_capacity(125);
         for c in Code::all() {
             let elims = self.verifiers.iter().map(|card| Self::expected_eliminations(card, c));
-            let total_elims = elims.sorted().rev().take(3).sum::<Rational32>();
+            let total_elims = elims.sorted().rev().take(self.questions_per_round()).sum::<Rational32>();
             questions.push((total_elims, c));
         }
+        // Among equal scores, the last code is preferred
         questions.sort_by_key(|(e, _)| *e);
-        let (e, c) = questions.pop().unwrap();
+        questions.reverse();
+        questions
+    }
+
+    // Finds the best question to ask with the elimination heuristic
+    pub fn best_question(&self) -> Code {
         if self.verbosity == SolverVerbosity::Verbose {
-            println!("Expected number of eliminations from the question: {:.1}", *e.numer() as f32 / *e.denom() as f32)
+            println!("Best questions:");
+            for q in self.ranked_questions(5) {
+                let elims = q.verifiers.iter().map(|&v| format!("{} {:.1}", "ABCDEF".chars().nth(v).unwrap(), q.eliminations[v]));
+                println!("{} │ {:.1} │ {}", q.code, q.score, elims.format(", "));
+            }
         }
-        c
+        self.scores()[0].1
+    }
+
+    // The n best questions, with their expected eliminations for each verifier and the verifiers to ask them to
+    pub fn ranked_questions(&self, n: usize) -> Vec<RankedQuestion> {
+        self.scores().into_iter().take(n).map(|(score, code)| {
+            let elims = self.verifiers.iter().map(|card| Self::expected_eliminations(card, code)).collect_vec();
+            // The verifiers are asked by decreasing eliminations, the last one first among equals
+            let verifiers = (0..elims.len()).filter(|&v| elims[v] > Rational32::ZERO)
+                .sorted_by_key(|&v| (std::cmp::Reverse(elims[v]), std::cmp::Reverse(v)))
+                .take(self.questions_per_round()).collect();
+            RankedQuestion { code, score: to_f32(score), eliminations: elims.into_iter().map(to_f32).collect(), verifiers }
+        }).collect()
     }
 
     // Checks if the given set of constraints are valid or not
-    fn valid_constraints(constraints: &Vec<Constraint>) -> bool {
+    pub fn valid_constraints(constraints: &[Constraint]) -> bool {
         // The constraints must all come from different cards (for nightmare mode only)
         if !constraints.iter().map(|c| c.group()).all_unique() {
             return false;
@@ -183,7 +445,7 @@ impl<V: Verifier> Solver<V> {
 
     // Eliminates impossible constraints, that is, constraints for which no combination
     // of the other constraints gives valid solutions
-    fn eliminate(&mut self) -> Result<(), SolverError> {
+    pub fn eliminate(&mut self) -> Result<(), SolverError> {
         let mut num_elims = 0;
         loop {
             let n = self.eliminate_step();
@@ -200,7 +462,7 @@ impl<V: Verifier> Solver<V> {
         let mut impossible: Vec<_> = self.verifiers.iter().map(|cs| vec![true; cs.len()]).collect();
 
         for idx in self.verifiers.iter().map(|cs| 0..cs.len()).multi_cartesian_product() {
-            let constraints = self.verifiers.iter().zip(idx.iter().copied()).map(|(cs, i)| cs[i]).collect();
+            let constraints: Vec<_> = self.verifiers.iter().zip(idx.iter().copied()).map(|(cs, i)| cs[i]).collect();
             if Self::valid_constraints(&constraints) {
                 impossible.iter_mut().zip(idx.iter().copied()).for_each(|(imp, i)| imp[i] = false)
             }
@@ -215,20 +477,20 @@ impl<V: Verifier> Solver<V> {
         num_elim
     }
 
-    fn best_verifier_for_question(&self, code: Code) -> Option<usize> {
+    pub fn best_verifier_for_question(&self, code: Code) -> Option<usize> {
         let elims = self.verifiers.iter().map(|v| Self::expected_eliminations(v, code));
         let (v_idx, e) = elims.enumerate().max_by_key(|(_, e)| *e).unwrap();
         if self.verbosity == SolverVerbosity::Verbose {
             if e == Rational32::ZERO {
                 println!("No more information from question.")
             } else {
-                println!("Expected number of eliminations from the answer: {:.1}", *e.numer() as f32 / *e.denom() as f32);
+                println!("Expected number of eliminations from the answer: {:.1}", to_f32(e));
             }
         }
         if e == Rational32::ZERO { None } else { Some(v_idx) } 
     }
 
-    fn expected_eliminations(verifier: &Vec<Constraint>, code: Code) -> Rational32 {
+    fn expected_eliminations(verifier: &[Constraint], code: Code) -> Rational32 {
         let counts = verifier.iter().map(|c| c.accepts(code)).counts();
         let n0 = counts.get(&false).copied().unwrap_or(0) as i32;
         let n1 = counts.get(&true).copied().unwrap_or(0) as i32;
@@ -237,24 +499,97 @@ impl<V: Verifier> Solver<V> {
 
     pub fn question(&mut self, code: Code, verifier: usize) -> Result<bool, SolverError> {
         // Retrieve the card that we are querying and ask the user for the result
-        let constraints = &mut self.verifiers[verifier];
         let answer = self.verifier.accepts(verifier, code);
-        
+        self.answer(code, verifier, answer)?;
+        Ok(answer)
+    }
+
+    // Applies the answer of a verifier to a code, and returns the number of constraints it eliminated
+    pub fn answer(&mut self, code: Code, verifier: usize, accepted: bool) -> Result<usize, SolverError> {
+        let num_before = self.num_constraints();
+
         // From the answer, eliminate the constraints that didn't agree
+        let constraints = self.verifiers.get_mut(verifier).ok_or(SolverError::UnknownVerifier(verifier))?;
         let len_before = constraints.len();
-        constraints.retain(|c| c.accepts(code) == answer);
+        constraints.retain(|c| c.accepts(code) == accepted);
         if self.verbosity == SolverVerbosity::Verbose {
             println!("Number of eliminations from answer: {}", len_before - constraints.len());
         }
 
         // Eliminate constraints
         self.eliminate()?;
-        Ok(answer)
+        Ok(num_before - self.num_constraints())
+    }
+
+    // The expected number of constraints that a question eliminates on its own, before any deduction,
+    // which is zero for a verifier that is not part of the game
+    pub fn expected_eliminations_of(&self, code: Code, verifier: usize) -> f32 {
+        self.verifiers.get(verifier).map_or(0.0, |cs| to_f32(Self::expected_eliminations(cs, code)))
+    }
+
+    // The number of constraints that an answer eliminates on its own, before any deduction
+    pub fn eliminations_of(&self, code: Code, verifier: usize, accepted: bool) -> usize {
+        self.verifiers.get(verifier).map_or(0, |cs| cs.iter().filter(|c| c.accepts(code) != accepted).count())
+    }
+
+    // The next question of Solver::solve, given the answers recorded so far: the code of the last round is
+    // asked to other verifiers while they are worth it and the round is not full, then a new code is picked
+    pub fn next_question(&self) -> Option<(Code, usize)> {
+        self.last_round().filter(|(_, asked)| *asked < self.questions_per_round())
+            .and_then(|(code, _)| self.best_verifier_for_question(code).map(|v| (code, v)))
+            .or_else(|| self.suggestion())
+    }
+
+    // The question that the solver would ask, along with the verifier to ask it to
+    pub fn suggestion(&self) -> Option<(Code, usize)> {
+        let code = self.best_question();
+        self.best_verifier_for_question(code).map(|v| (code, v))
+    }
+
+    // The codes that are still possible
+    pub fn possible_codes(&self) -> Vec<Code> {
+        self.worlds().filter_map(|w| Constraint::inter(w.into_iter()).solution()).unique().sorted().collect()
+    }
+
+    // Checks that a question can be asked after the answers recorded so far: asking the code of the last round
+    // continues that round, which can't ask more verifiers than allowed, nor the same verifier twice
+    pub fn check_question(&self, code: Code, verifier: usize) -> Result<(), SolverError> {
+        if verifier >= self.verifiers.len() {
+            return Err(SolverError::UnknownVerifier(verifier));
+        }
+        match self.questions.last().zip(self.answers.last()) {
+            Some((&c, asked)) if c == code && asked.contains_key(&verifier) => Err(SolverError::VerifierAlreadyAsked(verifier)),
+            Some((&c, asked)) if c == code && asked.len() >= self.questions_per_round() => Err(SolverError::RoundIsFull),
+            _ => Ok(()),
+        }
+    }
+
+    // Applies answers that are already known, like the ones of rounds played without the solver.
+    // Consecutive answers to the same code make a round, even across calls. The solver is left
+    // unchanged if one of the answers is rejected.
+    pub fn record_answers(&mut self, answers: &[Answer]) -> Result<(), SolverError> where V: Clone {
+        let mut next = self.clone();
+        for a in answers {
+            next.check_question(a.code, a.verifier)?;
+            next.answer(a.code, a.verifier, a.accepted)?;
+            if next.questions.last() != Some(&a.code) {
+                next.questions.push(a.code);
+                next.answers.push(HashMap::new());
+            }
+            next.answers.last_mut().unwrap().insert(a.verifier, a.accepted);
+        }
+        *self = next;
+        Ok(())
+    }
+
+    // The code of the last round, with the number of verifiers it was asked to
+    pub fn last_round(&self) -> Option<(Code, usize)> {
+        Some((*self.questions.last()?, self.answers.last()?.len()))
     }
 
     pub fn round(&mut self, code: Code) -> Result<(), SolverError> {
         let mut answers = HashMap::new();
-        for _ in 0..3 {
+        for _ in 0..self.questions_per_round() {
             let v_idx = if let Some(i) = self.best_verifier_for_question(code) { i } else { break };
             let answer = self.question(code, v_idx)?;
             answers.insert(v_idx, answer);
@@ -267,8 +602,9 @@ impl<V: Verifier> Solver<V> {
         Ok(())
     }
 
-    pub fn solve(&mut self) -> Result<Code, SolverError> {
-        let mut round = 1;
+    pub fn solve(&mut self) -> Result<Solution, SolverError> {
+        // The rounds of answers that were recorded beforehand are counted too
+        let mut round = self.num_rounds() + 1;
         self.eliminate()?;
         
         loop {
@@ -281,11 +617,80 @@ impl<V: Verifier> Solver<V> {
             let c = self.best_question();
             self.round(c)?;
 
-            if let Some(sol) = self.has_solution()? {
-                return Ok(sol);
+            if let Some(code) = self.has_solution()? {
+                let assignment = self.assignment().expect("Every constraint comes from a card of the problem");
+                return Ok(Solution { code, assignment });
             }
 
             round += 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{catalogue::Catalogue, constraint::Constraint, games::{GameDatabase, GAMES_PATH}};
+    use super::{Answer, AutomaticVerifier, CommandLineVerifier, Solver, SolverError, SolverVerbosity};
+
+    #[test]
+    pub fn solver_finds_the_laws_of_the_verifiers() {
+        let db = GameDatabase::load(GAMES_PATH).unwrap();
+        for record in db.records().iter().take(9) {
+            let problem = record.problem().unwrap();
+            let mut solver = Solver::<AutomaticVerifier>::new(&problem).automatic_laws(Catalogue::builtin(), &record.laws).unwrap();
+            let solution = solver.solve().ok().unwrap();
+            for (a, &id) in solution.assignment.into_iter().zip(record.laws.iter()) {
+                assert!(a.law.is_equivalent_to(Catalogue::builtin().law(id).unwrap()), "{}", record.hash);
+                assert!(problem.cards[a.card].laws.contains(&a.law));
+            }
+        }
+    }
+
+    // Cards 11 and 48 of verifier B both have law 139, so no question can tell which one is used
+    #[test]
+    pub fn equivalent_laws_on_several_cards_are_known() {
+        let problem = "extreme 17 40 11 48 20 23 2 19".parse().unwrap();
+        let mut solver = Solver::<AutomaticVerifier>::new(&problem).automatic_laws(Catalogue::builtin(), &[85, 139, 121, 100]).unwrap();
+        let solution = solver.solve().ok().unwrap();
+        assert_eq!(solver.possible_assignments()[1].len(), 2);
+        assert_eq!(solution.assignment[1].law.id, Some(139));
+        assert_eq!(solution.code, Constraint::inter(solution.assignment.iter().map(|a| a.law.constraint)).solution().unwrap());
+    }
+
+    #[test]
+    pub fn questions_are_ranked() {
+        let solver = Solver::<CommandLineVerifier>::new(&"normal 16 31 46 48".parse().unwrap()).verbosity(SolverVerbosity::None);
+        let ranked = solver.ranked_questions(10);
+        assert_eq!(ranked.len(), 10);
+        assert_eq!(ranked[0].code, solver.best_question());
+        assert!(ranked.windows(2).all(|q| q[0].score >= q[1].score));
+        for q in ranked {
+            assert!(q.verifiers.len() <= 3 && q.verifiers.windows(2).all(|v| q.eliminations[v[0]] >= q.eliminations[v[1]]));
+            assert_eq!(q.verifiers.first().copied(), solver.best_verifier_for_question(q.code));
+        }
+    }
+
+    #[test]
+    pub fn recorded_answers_make_rounds() {
+        let problem = "normal 16 31 46 48".parse().unwrap();
+        let parse = |answers: &[&str]| -> Vec<Answer> { answers.iter().map(|a| a.parse().unwrap()).collect() };
+        let answers = parse(&["111:A=y", "111:B=n", "222:C=n"]);
+        let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(SolverVerbosity::None);
+        assert!(solver.record_answers(&answers).is_ok());
+        assert_eq!((solver.num_rounds(), solver.num_questions()), (2, 3));
+
+        let contradiction: Answer = "111:A=n".parse().unwrap();
+        let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(SolverVerbosity::None);
+        assert!(solver.record_answers(&[contradiction]).is_err());
+        assert!(matches!(solver.record_answers(&["111:F=y".parse().unwrap()]), Err(SolverError::UnknownVerifier(5))));
+
+        // A round asks a code to three different verifiers at most, and a rejected answer changes nothing
+        let mut solver = Solver::<CommandLineVerifier>::new(&problem).verbosity(SolverVerbosity::None);
+        assert!(matches!(solver.record_answers(&parse(&["111:A=y", "111:A=y"])), Err(SolverError::VerifierAlreadyAsked(0))));
+        assert_eq!(solver.num_questions(), 0);
+        assert!(solver.record_answers(&parse(&["111:A=y", "111:B=n", "111:C=n"])).is_ok());
+        assert!(matches!(solver.record_answers(&parse(&["111:D=n"])), Err(SolverError::RoundIsFull)));
+        assert!(matches!(solver.record_answers(&parse(&["222:D=y", "222:D=y"])), Err(SolverError::VerifierAlreadyAsked(3))));
+        assert_eq!((solver.num_rounds(), solver.num_questions()), (1, 3));
+    }
+}
@@ -0,0 +1,138 @@
+use itertools::Itertools;
+use json::JsonValue;
+use crate::{code::Code, error::Error, problem::Problem, solve::{CommandLineVerifier, Solver}};
+
+// The complete strategy of the solver for a problem: every question it asks, depending on the previous answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecisionTree {
+    Question { round: usize, code: Code, verifier: usize, yes: Box<DecisionTree>, no: Box<DecisionTree> },
+    Solved(Code),
+    Contradiction, // No assignment of laws gives these answers
+}
+
+// The position of the solver within a round: the code of the round and the number of verifiers already asked
+#[derive(Clone, Copy)]
+struct Position {
+    round: usize,
+    code: Option<Code>,
+    asked: usize,
+}
+
+fn letter(verifier: usize) -> char {
+    "ABCDEF".chars().nth(verifier).unwrap()
+}
+
+impl DecisionTree {
+    // Follows the same steps as Solver::solve, asking each question with both answers
+    pub fn of(problem: &Problem) -> Result<DecisionTree, Error> {
+        let solver = Solver::<CommandLineVerifier>::from_problem(problem)?;
+        Ok(Self::build(&solver, Position { round: 0, code: None, asked: 0 }))
+    }
+
+    fn build(solver: &Solver<CommandLineVerifier>, position: Position) -> DecisionTree {
+        // A round asks the same code to a few verifiers, depending on the strategy, and the solution is only checked between rounds
+        let question = position.code.filter(|_| position.asked < solver.questions_per_round())
+            .and_then(|c| solver.best_verifier_for_question(c).map(|v| (c, v)));
+        let (position, code, verifier) = match question {
+            Some((code, v)) => (position, code, v),
+            None => {
+                match solver.has_solution() {
+                    Ok(Some(code)) => return DecisionTree::Solved(code),
+                    Ok(None) => (),
+                    Err(_) => return DecisionTree::Contradiction,
+                }
+                let code = solver.best_question();
+                match solver.best_verifier_for_question(code) {
+                    Some(v) => (Position { round: position.round + 1, code: Some(code), asked: 0 }, code, v),
+                    // The remaining laws can't be told apart by any question
+                    None => return DecisionTree::Contradiction,
+                }
+            }
+        };
+
+        let next = Position { asked: position.asked + 1, ..position };
+        let branch = |accepted| {
+            let mut solver = solver.clone();
+            match solver.answer(code, verifier, accepted) {
+                Ok(_) => Self::build(&solver, next),
+                Err(_) => DecisionTree::Contradiction,
+            }
+        };
+        DecisionTree::Question { round: position.round, code, verifier, yes: Box::new(branch(true)), no: Box::new(branch(false)) }
+    }
+
+    // The codes that the strategy can end on, which are the possible solutions of the problem
+    pub fn solutions(&self) -> Vec<Code> {
+        match self {
+            DecisionTree::Question { yes, no, .. } => yes.solutions().into_iter().chain(no.solutions()).sorted().dedup().collect(),
+            DecisionTree::Solved(code) => vec![*code],
+            DecisionTree::Contradiction => vec![],
+        }
+    }
+
+    // The largest number of questions asked before reaching a solution
+    pub fn max_questions(&self) -> usize {
+        match self {
+            DecisionTree::Question { yes, no, .. } => 1 + yes.max_questions().max(no.max_questions()),
+            _ => 0,
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            DecisionTree::Question { round, code, verifier, yes, no } => json::object! {
+                "round": *round,
+                "code": code.to_string(),
+                "verifier": letter(*verifier).to_string(),
+                "yes": yes.to_json(),
+                "no": no.to_json(),
+            },
+            DecisionTree::Solved(code) => json::object! { "solution": code.to_string() },
+            DecisionTree::Contradiction => json::object! { "contradiction": true },
+        }
+    }
+
+    // A Graphviz graph of the tree, where the yes branches are drawn in green and the no branches in red
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph strategy {".to_string(), "  node [fontname=\"sans-serif\"];".to_string()];
+        self.dot_nodes(&mut lines, &mut 0);
+        lines.push("}".into());
+        lines.into_iter().map(|l| l + "\n").collect()
+    }
+
+    // Adds the node and its children to the graph, and returns the name of the node
+    fn dot_nodes(&self, lines: &mut Vec<String>, count: &mut usize) -> String {
+        let name = format!("n{}", count);
+        *count += 1;
+        match self {
+            DecisionTree::Question { round, code, verifier, yes, no } => {
+                lines.push(format!("  {} [label=\"Round {}\\n{} to {}\"];", name, round, code, letter(*verifier)));
+                for (child, label, color) in [(yes, "yes", "darkgreen"), (no, "no", "red")] {
+                    let child = child.dot_nodes(lines, count);
+                    lines.push(format!("  {} -> {} [label=\"{}\", color={}];", name, child, label, color));
+                }
+            }
+            DecisionTree::Solved(code) => lines.push(format!("  {} [label=\"{}\", shape=box, style=bold];", name, code)),
+            DecisionTree::Contradiction => lines.push(format!("  {} [label=\"impossible\", shape=plaintext];", name)),
+        }
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use crate::{analyze::analyze, problem::Problem};
+    use super::DecisionTree;
+
+    #[test]
+    pub fn strategy_covers_every_solution() {
+        let problem: Problem = "normal 16 31 46 48".parse().unwrap();
+        let tree = DecisionTree::of(&problem).unwrap();
+        let codes = analyze(&problem).unwrap().codes().into_iter().map(|(c, _)| c).sorted().collect::<Vec<_>>();
+        assert_eq!(tree.solutions(), codes);
+        assert!(tree.max_questions() > 0);
+        assert!(tree.to_dot().starts_with("digraph"));
+        assert_eq!(tree.to_json()["round"], 1);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{code::Code, error::Error, solve::{Assignment, Solver, SolverVerbosity, Verifier}};
+
+// What the solver would know after a given answer
+#[derive(Clone)]
+pub struct Hypothesis {
+    pub eliminated: usize,
+    pub laws: Vec<Vec<Assignment>>, // The laws that each verifier could still use
+    pub codes: Vec<Code>,
+}
+
+// The consequences of both answers to a question, or None for an answer that contradicts the previous ones
+#[derive(Clone)]
+pub struct WhatIf {
+    pub code: Code,
+    pub verifier: usize,
+    pub yes: Option<Hypothesis>,
+    pub no: Option<Hypothesis>,
+}
+
+impl WhatIf {
+    // Applies each answer to a copy of the solver, so that nothing is recorded
+    pub fn of<V: Verifier + Clone>(solver: &Solver<V>, code: Code, verifier: usize) -> Result<WhatIf, Error> {
+        if verifier >= solver.num_verifiers() {
+            return Err(Error::InvalidQuestion(format!("{}:{}", code, "ABCDEF".chars().nth(verifier).unwrap_or('?'))));
+        }
+        let hypothesis = |accepted| {
+            let mut solver = solver.clone().verbosity(SolverVerbosity::None);
+            let eliminated = solver.answer(code, verifier, accepted).ok()?;
+            Some(Hypothesis { eliminated, laws: solver.possible_assignments(), codes: solver.possible_codes() })
+        };
+        Ok(WhatIf { code, verifier, yes: hypothesis(true), no: hypothesis(false) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{problem::Problem, solve::{CommandLineVerifier, Solver}};
+    use super::WhatIf;
+
+    #[test]
+    pub fn both_answers_are_explored() {
+        let problem: Problem = "normal 4 9 11 14".parse().unwrap();
+        let solver = Solver::<CommandLineVerifier>::from_problem(&problem).unwrap();
+        let num_constraints = solver.num_constraints();
+
+        let what_if = WhatIf::of(&solver, "445".parse().unwrap(), 2).unwrap();
+        let (yes, no) = (what_if.yes.unwrap(), what_if.no.unwrap());
+        assert_eq!(yes.codes, vec!["221".parse().unwrap()]);
+        assert_eq!(no.codes, vec!["241".parse().unwrap()]);
+        assert!(yes.laws.iter().all(|laws| laws.len() == 1));
+        assert_eq!(solver.num_constraints(), num_constraints);
+
+        // Verifier B is known to reject every code with a 3
+        assert!(WhatIf::of(&solver, "333".parse().unwrap(), 1).unwrap().yes.is_none());
+        assert!(WhatIf::of(&solver, "333".parse().unwrap(), 4).is_err());
+    }
+}